@@ -0,0 +1,33 @@
+/*!
+Support code for the derive macro. Not public API.
+*/
+
+use crate::Diagnostic;
+
+/**
+Lets the derive ask whether a field is a [Diagnostic] without knowing its
+type. Called as `(&Wrap(&field)).as_diagnostic_source()`, method resolution
+picks [AsDiagnosticSource] when the field implements [Diagnostic], and only
+autorefs its way to [NotDiagnosticSource] when it doesn't.
+*/
+pub struct Wrap<'a, T>(pub &'a T);
+
+pub trait AsDiagnosticSource<'a> {
+    fn as_diagnostic_source(&self) -> Option<&'a dyn Diagnostic>;
+}
+
+impl<'a, T: Diagnostic> AsDiagnosticSource<'a> for Wrap<'a, T> {
+    fn as_diagnostic_source(&self) -> Option<&'a dyn Diagnostic> {
+        Some(self.0)
+    }
+}
+
+pub trait NotDiagnosticSource<'a> {
+    fn as_diagnostic_source(&self) -> Option<&'a dyn Diagnostic>;
+}
+
+impl<'a, T> NotDiagnosticSource<'a> for &Wrap<'a, T> {
+    fn as_diagnostic_source(&self) -> Option<&'a dyn Diagnostic> {
+        None
+    }
+}
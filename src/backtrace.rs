@@ -0,0 +1,83 @@
+use std::backtrace::{Backtrace, BacktraceStatus};
+
+/**
+Crates whose frames are just noise in a diagnostic's backtrace.
+*/
+const HIDDEN_CRATES: &[&str] = &["std", "core", "alloc", "thisdiagnostic"];
+
+/**
+Captures a backtrace if `RUST_LIB_BACKTRACE` or `RUST_BACKTRACE` asks for
+one.
+*/
+pub(crate) fn capture() -> Option<Backtrace> {
+    let backtrace = Backtrace::capture();
+    (backtrace.status() == BacktraceStatus::Captured).then_some(backtrace)
+}
+
+/**
+One function in a backtrace, with where it was called from when that's
+known.
+*/
+pub(crate) struct Frame {
+    pub(crate) symbol: String,
+    pub(crate) location: Option<String>,
+}
+
+/**
+The frames of `backtrace` that belong to the program itself, innermost
+first. std's runtime frames, from `__rust_begin_short_backtrace` on, are cut
+off, and frames in std or thisdiagnostic are left out.
+
+`Backtrace` doesn't expose its frames on stable, so this goes through its
+`Display` output instead.
+*/
+pub(crate) fn frames(backtrace: &Backtrace) -> Vec<Frame> {
+    let mut frames: Vec<Frame> = Vec::new();
+    for line in backtrace.to_string().lines() {
+        let line = line.trim();
+        if let Some(location) = line.strip_prefix("at ") {
+            if let Some(frame) = frames.last_mut() {
+                frame.location = Some(location.into());
+            }
+            continue;
+        }
+        // Inlined functions share their frame's number and show up without one.
+        let symbol = match line.split_once(": ") {
+            Some((idx, symbol)) if idx.chars().all(|c| c.is_ascii_digit()) => symbol,
+            _ => line,
+        };
+        frames.push(Frame {
+            symbol: symbol.into(),
+            location: None,
+        });
+    }
+
+    frames
+        .into_iter()
+        .take_while(|frame| !frame.symbol.contains("__rust_begin_short_backtrace"))
+        .filter(|frame| frame.symbol != "<unknown>" && !is_hidden(&frame.symbol))
+        .collect()
+}
+
+/**
+Whether `symbol` lives in one of the [HIDDEN_CRATES]. Trait methods count
+as their type's crate, unless the type isn't a path, like the `T` in
+`<T as core::convert::Into<U>>::into` or a `fn()` pointer.
+*/
+fn is_hidden(symbol: &str) -> bool {
+    let mut path = symbol.trim_start_matches(['<', '&']);
+    path = path.strip_prefix("mut ").unwrap_or(path);
+    path = path.strip_prefix("dyn ").unwrap_or(path);
+    if let Some((ty, trait_path)) = path.split_once(" as ") {
+        let is_path = ty.contains("::")
+            && ty
+                .split("::")
+                .next()
+                .is_some_and(|krate| krate.chars().all(|c| c.is_alphanumeric() || c == '_'));
+        if !is_path {
+            path = trait_path;
+        }
+    }
+    let krate = path.split("::").next().unwrap_or(path);
+    HIDDEN_CRATES.contains(&krate)
+}
@@ -0,0 +1,138 @@
+use std::error::Error;
+
+use crate::{Diagnostic, DiagnosticError, Severity};
+
+/**
+One error in a [DiagnosticError]'s source chain. Causes that are
+themselves a [DiagnosticError] or a `Box<dyn Diagnostic>`, or that the error
+above them returns from [Diagnostic::diagnostic_source], carry their
+diagnostic details, too.
+*/
+pub(crate) struct Cause {
+    pub(crate) message: String,
+    pub(crate) diagnostic: Option<CauseDiagnostic>,
+}
+
+pub(crate) struct CauseDiagnostic {
+    pub(crate) label: String,
+    pub(crate) help: Option<String>,
+    pub(crate) severity: Severity,
+}
+
+/**
+The message shown for `error`: its outermost context, if it has any, or its
+own message.
+*/
+pub(crate) fn headline(error: &DiagnosticError) -> String {
+    match error.details.context.last() {
+        Some(context) => context.clone(),
+        None => format!("{:#}", error.error),
+    }
+}
+
+/**
+Walks `error`'s source chain, outermost cause first. Context layers aren't
+included.
+
+A `Diagnostic` behind `dyn Error` can't be downcast without knowing its
+type, so alongside `source()` this follows `diagnostic_source()`, and treats
+a cause as a diagnostic when both point at the same value.
+*/
+pub(crate) fn sources(error: &DiagnosticError) -> Vec<Cause> {
+    let mut causes = Vec::new();
+    let mut next = error.error.source();
+    let mut next_diagnostic = diagnostic_source(error);
+    while let Some(cause) = next {
+        if let Some(diagnostic) = cause.downcast_ref::<DiagnosticError>() {
+            causes.push(Cause {
+                message: diagnostic.error.to_string(),
+                diagnostic: Some(CauseDiagnostic {
+                    label: diagnostic.label.clone(),
+                    help: diagnostic.help.clone(),
+                    severity: diagnostic.severity,
+                }),
+            });
+            next = diagnostic.error.source();
+            next_diagnostic = diagnostic_source(diagnostic);
+        } else if let Some(diagnostic) = cause
+            .downcast_ref::<Box<dyn Diagnostic>>()
+            .map(|diagnostic| &**diagnostic)
+            .or_else(|| next_diagnostic.filter(|diagnostic| same_value(*diagnostic, cause)))
+        {
+            causes.push(Cause {
+                message: diagnostic.to_string(),
+                diagnostic: Some(CauseDiagnostic {
+                    label: diagnostic.label(),
+                    help: diagnostic.help(),
+                    severity: diagnostic.severity(),
+                }),
+            });
+            next = diagnostic.source();
+            next_diagnostic = diagnostic.diagnostic_source();
+        } else {
+            causes.push(Cause {
+                message: cause.to_string(),
+                diagnostic: None,
+            });
+            next = cause.source();
+        }
+    }
+    causes
+}
+
+/**
+The `diagnostic_source()` of the [Diagnostic] `error` was made from, if it
+was made from one.
+*/
+fn diagnostic_source(error: &DiagnosticError) -> Option<&dyn Diagnostic> {
+    let as_diagnostic = error.details.as_diagnostic?;
+    as_diagnostic(&*error.error)?.diagnostic_source()
+}
+
+fn same_value(diagnostic: &dyn Diagnostic, error: &dyn Error) -> bool {
+    std::ptr::eq(
+        diagnostic as *const dyn Diagnostic as *const u8,
+        error as *const dyn Error as *const u8,
+    )
+}
+
+/**
+Everything shown under the [headline]: the inner context layers, then the
+error's own message if context took its place, then its source chain.
+
+Messages that just repeat the one before them are dropped. That's common
+with `#[error(transparent)]` and `#[error("{0}")]` wrappers. Diagnostic
+causes are kept for their label and help, with an empty message.
+*/
+pub(crate) fn collapsed_causes(error: &DiagnosticError) -> Vec<Cause> {
+    let mut all = Vec::new();
+    if let Some((_, inner)) = error.details.context.split_last() {
+        all.extend(inner.iter().rev().map(|context| Cause {
+            message: context.clone(),
+            diagnostic: None,
+        }));
+        all.push(Cause {
+            message: error.error.to_string(),
+            diagnostic: None,
+        });
+    }
+    all.extend(sources(error));
+
+    let mut previous = match error.details.context.last() {
+        Some(context) => context.clone(),
+        None => error.error.to_string(),
+    };
+    let mut causes = Vec::new();
+    for mut cause in all {
+        if cause.message == previous {
+            if cause.diagnostic.is_none() {
+                continue;
+            }
+            cause.message.clear();
+        } else {
+            previous = cause.message.clone();
+        }
+        causes.push(cause);
+    }
+    causes
+}
@@ -0,0 +1,370 @@
+use std::backtrace::Backtrace;
+use std::fmt;
+use std::io::IsTerminal;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use crate::backtrace;
+use crate::chain;
+use crate::narratable;
+use crate::snippet::Snippet;
+use crate::style::Painter;
+use crate::{
+    DiagnosticError, DiagnosticMetadata, LineIndex, NarratableReportHandler, Severity, Theme,
+};
+
+/**
+Renders a [DiagnosticError] for its `Debug` implementation, which is what
+`main` prints when it returns an error.
+
+Install one with [set_hook] to change how every diagnostic in the program is
+laid out. [DefaultReportHandler] is used otherwise. [ReportHandler::render]
+uses a handler directly, without installing it.
+*/
+pub trait ReportHandler: Send + Sync + 'static {
+    fn debug(&self, error: &DiagnosticError, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+
+    /**
+    Renders `error` with this handler to a string.
+    */
+    fn render(&self, error: &DiagnosticError) -> String {
+        struct Render<'a, H: ?Sized>(&'a H, &'a DiagnosticError);
+
+        impl<H: ReportHandler + ?Sized> fmt::Display for Render<'_, H> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.debug(self.1, f)
+            }
+        }
+
+        Render(self, error).to_string()
+    }
+}
+
+static HOOK: RwLock<Option<Arc<dyn ReportHandler>>> = RwLock::new(None);
+
+/**
+Installs a [ReportHandler] for all diagnostics, replacing any handler
+installed before.
+
+### Example
+```
+use std::fmt;
+use thisdiagnostic::{DiagnosticError, ReportHandler};
+
+struct Terse;
+
+impl ReportHandler for Terse {
+    fn debug(&self, error: &DiagnosticError, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] {}", error.label, error.error)
+    }
+}
+
+thisdiagnostic::set_hook(Box::new(Terse));
+```
+*/
+pub fn set_hook(handler: Box<dyn ReportHandler>) {
+    let mut hook = HOOK.write().unwrap_or_else(|err| err.into_inner());
+    *hook = Some(Arc::from(handler));
+}
+
+pub(crate) fn hook() -> Arc<dyn ReportHandler> {
+    let hook = HOOK.read().unwrap_or_else(|err| err.into_inner());
+    match &*hook {
+        Some(handler) => handler.clone(),
+        None => Arc::new(DefaultReportHandler::new()),
+    }
+}
+
+/**
+When to color diagnostics.
+*/
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorChoice {
+    /**
+    Color when stderr is a terminal. `NO_COLOR` turns colors off, and
+    `CLICOLOR_FORCE` turns them on even when it isn't a terminal.
+    `CLICOLOR=0` turns them off, too. `NO_COLOR` wins over the others.
+    */
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    /**
+    Whether this choice means coloring right now, going by the environment
+    and stderr for [ColorChoice::Auto].
+    */
+    pub fn should_color(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                let var = |name| std::env::var_os(name).filter(|value| !value.is_empty());
+                if var("NO_COLOR").is_some() {
+                    false
+                } else if var("CLICOLOR_FORCE").is_some_and(|value| value != "0") {
+                    true
+                } else if var("CLICOLOR").is_some_and(|value| value == "0") {
+                    false
+                } else {
+                    std::io::stderr().is_terminal()
+                }
+            }
+        }
+    }
+}
+
+/**
+The standard colored layout: the label and location, a snippet of the
+source for parse errors, then the error message, help and url, and finally
+the active `tracing` spans and the backtrace, if they were captured.
+
+Setting `THISDIAGNOSTIC_NARRATE=1` switches it to the plain sentences of
+[NarratableReportHandler] instead.
+*/
+#[derive(Debug, Default, Clone)]
+pub struct DefaultReportHandler {
+    color: ColorChoice,
+    theme: Theme,
+    hide_backtrace: bool,
+}
+
+impl DefaultReportHandler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+    Sets when to color output. Defaults to [ColorChoice::Auto].
+    */
+    pub fn color(mut self, color: ColorChoice) -> Self {
+        self.color = color;
+        self
+    }
+
+    /**
+    Sets the colors and glyphs to draw with. Defaults to [Theme::new].
+    */
+    pub fn theme(mut self, theme: Theme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /**
+    Sets whether to print captured backtraces. Defaults to `true`, though
+    they're only captured when `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` is
+    set.
+    */
+    pub fn backtrace(mut self, backtrace: bool) -> Self {
+        self.hide_backtrace = !backtrace;
+        self
+    }
+}
+
+impl ReportHandler for DefaultReportHandler {
+    fn debug(&self, error: &DiagnosticError, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if narratable::narrate_from_env() {
+            return NarratableReportHandler::new().debug(error, f);
+        }
+
+        let painter = Painter {
+            enabled: self.color.should_color(),
+            theme: &self.theme,
+        };
+        let styles = &self.theme.styles;
+        let severity = self.theme.severity(error.severity);
+
+        let label = match error.severity {
+            Severity::Error => error.label.clone(),
+            severity => format!("{}: {}", severity, error.label),
+        };
+        write!(f, "{}", painter.paint(severity, label))?;
+        match error.details.meta.as_ref() {
+            Some(DiagnosticMetadata::Net { ref url }) => {
+                write!(f, " @ {}", painter.paint(styles.link, url))?;
+            }
+            Some(DiagnosticMetadata::Fs { ref path }) => {
+                write!(
+                    f,
+                    " @ {}",
+                    painter.paint(styles.link, path.to_string_lossy())
+                )?;
+            }
+            Some(DiagnosticMetadata::Parse {
+                input: _,
+                row,
+                col,
+                path,
+            }) => {
+                write_location(f, painter, *row, *col, path.as_ref())?;
+            }
+            Some(DiagnosticMetadata::ParseSpan { input, span, path }) => {
+                let (row, col) = LineIndex::new(input).location(span.offset);
+                write_location(f, painter, row, col, path.as_ref())?;
+            }
+            None => {}
+        }
+        write!(f, "\n\n")?;
+        let snippet = error
+            .details
+            .meta
+            .as_ref()
+            .and_then(|meta| Snippet::new(meta, &error.details.labels));
+        if let Some(snippet) = snippet {
+            snippet.render(f, severity, painter)?;
+        }
+        write!(f, "{}", chain::headline(error))?;
+        write_causes(f, painter, error)?;
+        if let Some(help) = &error.help {
+            write!(f, "\n\n{}: {}", painter.paint(styles.help, "help"), help)?;
+        }
+        if let Some(url) = &error.url {
+            write!(
+                f,
+                "\n\nfor more information see {}",
+                painter.paint(styles.link, url)
+            )?;
+        }
+        #[cfg(feature = "tracing")]
+        if let Some(span_trace) = &error.details.span_trace {
+            write_span_trace(f, painter, span_trace)?;
+        }
+        if let Some(backtrace) = error
+            .details
+            .backtrace
+            .as_ref()
+            .filter(|_| !self.hide_backtrace)
+        {
+            write_backtrace(f, painter, backtrace)?;
+        }
+        Ok(())
+    }
+}
+
+/**
+Writes the spans that were active when the error was created, innermost
+first.
+*/
+#[cfg(feature = "tracing")]
+fn write_span_trace(
+    f: &mut fmt::Formatter<'_>,
+    painter: Painter,
+    span_trace: &tracing_error::SpanTrace,
+) -> fmt::Result {
+    let spans = crate::trace::spans(span_trace)
+        .into_iter()
+        .map(|span| {
+            let mut lines = vec![span.name];
+            if !span.fields.is_empty() {
+                lines.push(format!("    with {}", span.fields));
+            }
+            if let Some(location) = &span.location {
+                let location = painter.paint(painter.theme.styles.position, location);
+                lines.push(format!("    at {}", location));
+            }
+            lines
+        })
+        .collect::<Vec<_>>();
+    write_numbered(f, "In spans", &spans)
+}
+
+/**
+Writes the program's own frames of `backtrace`.
+*/
+fn write_backtrace(
+    f: &mut fmt::Formatter<'_>,
+    painter: Painter,
+    backtrace: &Backtrace,
+) -> fmt::Result {
+    let frames = backtrace::frames(backtrace)
+        .into_iter()
+        .map(|frame| {
+            let mut lines = vec![frame.symbol];
+            if let Some(location) = &frame.location {
+                let location = painter.paint(painter.theme.styles.position, location);
+                lines.push(format!("    at {}", location));
+            }
+            lines
+        })
+        .collect::<Vec<_>>();
+    write_numbered(f, "Backtrace", &frames)
+}
+
+/**
+Writes the source chain, each cause with its label and help if it's a
+diagnostic.
+*/
+fn write_causes(
+    f: &mut fmt::Formatter<'_>,
+    painter: Painter,
+    error: &DiagnosticError,
+) -> fmt::Result {
+    let causes = chain::collapsed_causes(error)
+        .into_iter()
+        .map(|cause| {
+            let mut lines = Vec::new();
+            if let Some(diagnostic) = &cause.diagnostic {
+                let style = painter.theme.severity(diagnostic.severity);
+                lines.push(painter.paint(style, &diagnostic.label).to_string());
+            }
+            lines.extend(cause.message.lines().map(String::from));
+            if let Some(help) = cause.diagnostic.as_ref().and_then(|d| d.help.as_ref()) {
+                let prefix = painter.paint(painter.theme.styles.help, "help");
+                lines.push(format!("{}: {}", prefix, help));
+            }
+            lines
+        })
+        .collect::<Vec<_>>();
+    write_numbered(f, "Caused by", &causes)
+}
+
+/**
+Writes an indented list under `title`, numbered from zero, with each entry's
+first line after its number and the rest lined up beneath it. Nothing is
+written when there are no entries.
+*/
+fn write_numbered(f: &mut fmt::Formatter<'_>, title: &str, entries: &[Vec<String>]) -> fmt::Result {
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    write!(f, "\n\n{}:", title)?;
+    let width = (entries.len() - 1).to_string().len();
+    let indent = format!("\n{}", " ".repeat(4 + width + 2));
+    for (idx, lines) in entries.iter().enumerate() {
+        write!(
+            f,
+            "\n    {:>width$}: {}",
+            idx,
+            lines.join(&indent),
+            width = width
+        )?;
+    }
+    Ok(())
+}
+
+fn write_location(
+    f: &mut fmt::Formatter<'_>,
+    painter: Painter,
+    row: usize,
+    col: usize,
+    path: Option<&PathBuf>,
+) -> fmt::Result {
+    let styles = &painter.theme.styles;
+    write!(
+        f,
+        " - line: {}, col: {}",
+        painter.paint(styles.position, row),
+        painter.paint(styles.position, col)
+    )?;
+    if let Some(path) = path {
+        write!(
+            f,
+            " @ {}",
+            painter.paint(styles.link, path.to_string_lossy())
+        )?;
+    }
+    Ok(())
+}
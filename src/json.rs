@@ -0,0 +1,291 @@
+use std::fmt::{self, Write};
+use std::io;
+
+use crate::chain;
+use crate::{DiagnosticError, DiagnosticMetadata, LineIndex, ReportHandler, SourceSpan};
+
+/**
+Renders diagnostics as JSON, for tools that consume them programmatically.
+
+Each diagnostic is an object with these keys, all of which are always
+present:
+
+* `label`: the diagnostic's label.
+* `message`: the error's own message.
+* `severity`: `"error"`, `"warning"` or `"advice"`.
+* `help`, `url`: strings, or `null`.
+* `metadata`: `null`, or an object whose `kind` is one of
+  * `"net"`, with a `url`,
+  * `"fs"`, with a `path`,
+  * `"parse"`, with a `path` (or `null`), the one-based `row` and `col`, and
+    the byte `offset` and `len` of the span, which are `null` for
+    row/col-only metadata.
+* `context`: the context added with `WrapDiagnostic`, outermost first.
+* `causes`: the messages of the error's source chain, outermost first.
+* `spans`: the labeled spans, each with `offset`, `len`, `row`, `col`,
+  `label` (or `null`) and `primary`. `row` and `col` are `null` when there's
+  no parse metadata to resolve them against.
+
+By default the object is pretty-printed. In line-delimited mode it's written
+on a single line, so [JsonReportHandler::write_all] can stream many
+diagnostics, one per line.
+
+### Example
+```
+use thisdiagnostic::{IntoDiagnostic, JsonReportHandler, ReportHandler};
+
+let err = "x".parse::<u8>().into_diagnostic("mytool::parse").unwrap_err();
+let json = JsonReportHandler::new().line_delimited(true).render(&err);
+assert!(json.starts_with(r#"{"label":"mytool::parse","message":"invalid digit"#));
+```
+*/
+#[derive(Debug, Default, Clone)]
+pub struct JsonReportHandler {
+    line_delimited: bool,
+}
+
+impl JsonReportHandler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+    Writes each diagnostic on a single line instead of pretty-printing it.
+    */
+    pub fn line_delimited(mut self, line_delimited: bool) -> Self {
+        self.line_delimited = line_delimited;
+        self
+    }
+
+    /**
+    Writes each of `errors` to `out`, ending every one with a newline. In
+    line-delimited mode, that's one diagnostic per line.
+    */
+    pub fn write_all<'a>(
+        &self,
+        mut out: impl io::Write,
+        errors: impl IntoIterator<Item = &'a DiagnosticError>,
+    ) -> io::Result<()> {
+        for error in errors {
+            writeln!(out, "{}", self.render(error))?;
+        }
+        out.flush()
+    }
+}
+
+impl ReportHandler for JsonReportHandler {
+    fn debug(&self, error: &DiagnosticError, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = to_json(error);
+        if self.line_delimited {
+            json.write_compact(f)
+        } else {
+            json.write_pretty(f, 0)
+        }
+    }
+}
+
+fn to_json(error: &DiagnosticError) -> Json {
+    let input = match error.details.meta.as_ref() {
+        Some(DiagnosticMetadata::Parse { input, .. })
+        | Some(DiagnosticMetadata::ParseSpan { input, .. }) => Some(LineIndex::new(input)),
+        _ => None,
+    };
+
+    let metadata = match error.details.meta.as_ref() {
+        None => Json::Null,
+        Some(DiagnosticMetadata::Net { url }) => {
+            Json::Object(vec![("kind", "net".into()), ("url", url.as_str().into())])
+        }
+        Some(DiagnosticMetadata::Fs { path }) => Json::Object(vec![
+            ("kind", "fs".into()),
+            ("path", path.to_string_lossy().as_ref().into()),
+        ]),
+        Some(DiagnosticMetadata::Parse { row, col, path, .. }) => {
+            parse_metadata(path.as_deref(), *row, *col, None)
+        }
+        Some(DiagnosticMetadata::ParseSpan { input, span, path }) => {
+            let (row, col) = LineIndex::new(input).location(span.offset);
+            parse_metadata(path.as_deref(), row, col, Some(*span))
+        }
+    };
+
+    let causes = chain::sources(error)
+        .into_iter()
+        .map(|cause| Json::from(cause.message.as_str()))
+        .collect();
+
+    let spans = error
+        .details
+        .labels
+        .iter()
+        .map(|label| {
+            let (row, col) = match &input {
+                Some(index) => {
+                    let (row, col) = index.location(label.span.offset);
+                    (row.into(), col.into())
+                }
+                None => (Json::Null, Json::Null),
+            };
+            Json::Object(vec![
+                ("offset", label.span.offset.into()),
+                ("len", label.span.len.into()),
+                ("row", row),
+                ("col", col),
+                ("label", label.label.as_deref().into()),
+                ("primary", Json::Bool(label.primary)),
+            ])
+        })
+        .collect();
+
+    Json::Object(vec![
+        ("label", error.label.as_str().into()),
+        ("message", error.error.to_string().as_str().into()),
+        ("severity", error.severity.to_string().as_str().into()),
+        ("help", error.help.as_deref().into()),
+        ("url", error.url.as_deref().into()),
+        ("metadata", metadata),
+        (
+            "context",
+            Json::Array(
+                error
+                    .details
+                    .context
+                    .iter()
+                    .rev()
+                    .map(|context| context.as_str().into())
+                    .collect(),
+            ),
+        ),
+        ("causes", Json::Array(causes)),
+        ("spans", Json::Array(spans)),
+    ])
+}
+
+fn parse_metadata(
+    path: Option<&std::path::Path>,
+    row: usize,
+    col: usize,
+    span: Option<SourceSpan>,
+) -> Json {
+    Json::Object(vec![
+        ("kind", "parse".into()),
+        (
+            "path",
+            path.map(|path| path.to_string_lossy().into_owned())
+                .as_deref()
+                .into(),
+        ),
+        ("row", row.into()),
+        ("col", col.into()),
+        ("offset", span.map(|span| span.offset).into()),
+        ("len", span.map(|span| span.len).into()),
+    ])
+}
+
+/**
+Just enough of a JSON value to write diagnostics out with.
+*/
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Number(usize),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+}
+
+impl Json {
+    pub(crate) fn write_compact(&self, out: &mut impl Write) -> fmt::Result {
+        match self {
+            Json::Array(items) => {
+                out.write_char('[')?;
+                for (idx, item) in items.iter().enumerate() {
+                    if idx > 0 {
+                        out.write_char(',')?;
+                    }
+                    item.write_compact(out)?;
+                }
+                out.write_char(']')
+            }
+            Json::Object(entries) => {
+                out.write_char('{')?;
+                for (idx, (key, value)) in entries.iter().enumerate() {
+                    if idx > 0 {
+                        out.write_char(',')?;
+                    }
+                    write_string(out, key)?;
+                    out.write_char(':')?;
+                    value.write_compact(out)?;
+                }
+                out.write_char('}')
+            }
+            Json::Null => out.write_str("null"),
+            Json::Bool(value) => write!(out, "{}", value),
+            Json::Number(value) => write!(out, "{}", value),
+            Json::String(value) => write_string(out, value),
+        }
+    }
+
+    pub(crate) fn write_pretty(&self, out: &mut impl Write, depth: usize) -> fmt::Result {
+        let indent = |out: &mut dyn Write, depth: usize| out.write_str(&"  ".repeat(depth));
+        match self {
+            Json::Array(items) if !items.is_empty() => {
+                out.write_str("[\n")?;
+                for (idx, item) in items.iter().enumerate() {
+                    indent(out, depth + 1)?;
+                    item.write_pretty(out, depth + 1)?;
+                    out.write_str(if idx + 1 < items.len() { ",\n" } else { "\n" })?;
+                }
+                indent(out, depth)?;
+                out.write_char(']')
+            }
+            Json::Object(entries) if !entries.is_empty() => {
+                out.write_str("{\n")?;
+                for (idx, (key, value)) in entries.iter().enumerate() {
+                    indent(out, depth + 1)?;
+                    write_string(out, key)?;
+                    out.write_str(": ")?;
+                    value.write_pretty(out, depth + 1)?;
+                    out.write_str(if idx + 1 < entries.len() { ",\n" } else { "\n" })?;
+                }
+                indent(out, depth)?;
+                out.write_char('}')
+            }
+            other => other.write_compact(out),
+        }
+    }
+}
+
+fn write_string(out: &mut impl Write, value: &str) -> fmt::Result {
+    out.write_char('"')?;
+    for c in value.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+impl From<&str> for Json {
+    fn from(value: &str) -> Self {
+        Json::String(value.into())
+    }
+}
+
+impl From<usize> for Json {
+    fn from(value: usize) -> Self {
+        Json::Number(value)
+    }
+}
+
+impl<T: Into<Json>> From<Option<T>> for Json {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Json::Null, Into::into)
+    }
+}
@@ -1,13 +1,40 @@
 #![doc = include_str!("../README.md")]
 
+use std::backtrace::Backtrace;
 use std::fmt;
 use std::path::PathBuf;
 
-use colored::Colorize;
 use thiserror::Error;
 
+pub use handler::{set_hook, ColorChoice, DefaultReportHandler, ReportHandler};
+pub use json::JsonReportHandler;
+pub use line_index::LineIndex;
+pub use narratable::NarratableReportHandler;
+pub use sarif::SarifReport;
+pub use span::{LabeledSpan, SourceSpan};
+pub use style::{Color, Style};
+pub use theme::{Glyphs, Styles, Theme};
 pub use thisdiagnostic_derive::Diagnostic;
 
+mod backtrace;
+mod chain;
+mod handler;
+mod json;
+mod line_index;
+#[cfg(feature = "lsp")]
+pub mod lsp;
+mod narratable;
+mod sarif;
+mod snippet;
+mod span;
+mod style;
+mod theme;
+#[cfg(feature = "tracing")]
+mod trace;
+
+#[doc(hidden)]
+pub mod __private;
+
 /**
 Wrapper for errors that that includes a bit more additional metadata and includes additional details.
 */
@@ -17,47 +44,163 @@ pub struct DiagnosticError {
     pub error: Box<dyn std::error::Error + Send + Sync>,
     pub label: String,
     pub help: Option<String>,
+    pub severity: Severity,
+    pub url: Option<String>,
+    pub details: Box<DiagnosticDetails>,
+}
+
+/**
+The parts of a [DiagnosticError] most errors leave empty. They're boxed so a
+[DiagnosticResult] stays small enough to return by value.
+
+Which fields there are depends on the enabled features, so start from
+`DiagnosticDetails::default()` and set the ones you need.
+*/
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct DiagnosticDetails {
+    /**
+    What the diagnostic is about, from [Diagnostic::meta]. This used to be
+    `DiagnosticError::meta`.
+    */
     pub meta: Option<DiagnosticMetadata>,
+    pub labels: Vec<LabeledSpan>,
+    /**
+    Messages added by [WrapDiagnostic] as the error bubbled up, innermost
+    first. The outermost one is shown in place of the error's own message,
+    which moves into the cause chain.
+    */
+    pub context: Vec<String>,
+    /**
+    Where the error was turned into a [DiagnosticError]. Only captured when
+    `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` is set.
+    */
+    pub backtrace: Option<Backtrace>,
+    /**
+    The `tracing` spans that were active when the error was turned into a
+    [DiagnosticError]. Only captured with the `tracing` feature, and when a
+    `tracing_error::ErrorLayer` is installed.
+    */
+    #[cfg(feature = "tracing")]
+    pub span_trace: Option<tracing_error::SpanTrace>,
+    /**
+    Gets the original [Diagnostic] back out of the boxed error, so the cause
+    chain can follow [Diagnostic::diagnostic_source].
+    */
+    pub(crate) as_diagnostic: Option<AsDiagnostic>,
+}
+
+type AsDiagnostic = for<'a> fn(&'a (dyn std::error::Error + 'static)) -> Option<&'a dyn Diagnostic>;
+
+fn as_diagnostic<'a, E: Diagnostic>(
+    error: &'a (dyn std::error::Error + 'static),
+) -> Option<&'a dyn Diagnostic> {
+    error
+        .downcast_ref::<E>()
+        .map(|error| error as &dyn Diagnostic)
+}
+
+impl DiagnosticDetails {
+    /**
+    Details with just the given metadata and labels, and whatever traces can
+    be captured right now.
+    */
+    fn capture(meta: Option<DiagnosticMetadata>, labels: Vec<LabeledSpan>) -> Box<Self> {
+        Box::new(Self {
+            meta,
+            labels,
+            context: Vec::new(),
+            backtrace: backtrace::capture(),
+            #[cfg(feature = "tracing")]
+            span_trace: trace::capture(),
+            as_diagnostic: None,
+        })
+    }
 }
 
 impl fmt::Debug for DiagnosticError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if f.alternate() {
             return fmt::Debug::fmt(&self.error, f);
-        } else {
-            write!(f, "{}", self.label.red())?;
-            match &self.meta {
-                Some(DiagnosticMetadata::Net { ref url }) => {
-                    write!(f, " @ {}", url.cyan().underline())?;
-                }
-                Some(DiagnosticMetadata::Fs { ref path }) => {
-                    write!(f, " @ {}", path.to_string_lossy().cyan().underline())?;
-                }
-                Some(DiagnosticMetadata::Parse {
-                    input: _input,
-                    row,
-                    col,
-                    path,
-                }) => {
-                    write!(
-                        f,
-                        " - line: {}, col: {}",
-                        row.to_string().green(),
-                        col.to_string().green()
-                    )?;
-                    if let Some(path) = path {
-                        write!(f, " @ {}", path.to_string_lossy().cyan().underline())?;
-                    }
-                }
-                None => {}
-            }
-            write!(f, "\n\n")?;
-            write!(f, "{:#}", self.error)?;
-            if let Some(help) = &self.help {
-                write!(f, "\n\n{}: {}", "help".yellow(), help)?;
+        }
+        handler::hook().debug(self, f)
+    }
+}
+
+impl DiagnosticError {
+    /**
+    A diagnostic with just an error and a label, and nothing else to say.
+    */
+    fn plain(error: Box<dyn std::error::Error + Send + Sync>, label: String) -> Self {
+        Self {
+            error,
+            label,
+            help: None,
+            severity: Severity::Error,
+            url: None,
+            details: DiagnosticDetails::capture(None, Vec::new()),
+        }
+    }
+
+    /**
+    Whether the wrapped error, or any error in its source chain, is an `E`.
+    */
+    pub fn is<E: std::error::Error + 'static>(&self) -> bool {
+        self.downcast_ref::<E>().is_some()
+    }
+
+    /**
+    Finds the first `E` among the wrapped error and its source chain, looking
+    through any [DiagnosticError]s along the way. Context layers added with
+    [WrapDiagnostic] don't get in the way.
+    */
+    pub fn downcast_ref<E: std::error::Error + 'static>(&self) -> Option<&E> {
+        let mut next: Option<&(dyn std::error::Error + 'static)> = Some(&*self.error);
+        while let Some(error) = next {
+            if let Some(error) = error.downcast_ref::<E>() {
+                return Some(error);
             }
+            next = match error.downcast_ref::<DiagnosticError>() {
+                Some(diagnostic) => Some(&*diagnostic.error),
+                None => error.source(),
+            };
         }
-        Ok(())
+        None
+    }
+
+    /**
+    The wrapped error, if it's an `E`. Unlike [DiagnosticError::downcast_ref],
+    this can't reach into the source chain, which is only available by
+    shared reference, but it does look inside a wrapped [DiagnosticError].
+    */
+    pub fn downcast_mut<E: std::error::Error + 'static>(&mut self) -> Option<&mut E> {
+        if self.error.is::<E>() {
+            return self.error.downcast_mut::<E>();
+        }
+        self.error
+            .downcast_mut::<DiagnosticError>()
+            .and_then(|diagnostic| diagnostic.downcast_mut::<E>())
+    }
+
+    /**
+    Takes the wrapped error out, if it's an `E`, or gives the whole
+    diagnostic back otherwise. Like [DiagnosticError::downcast_mut], this
+    doesn't reach into the source chain, but does look inside a wrapped
+    [DiagnosticError].
+    */
+    pub fn downcast<E: std::error::Error + 'static>(self) -> Result<E, Self> {
+        let error = match self.error.downcast::<E>() {
+            Ok(error) => return Ok(*error),
+            Err(error) => error,
+        };
+        let error = match error.downcast::<DiagnosticError>() {
+            Ok(inner) => match inner.downcast::<E>() {
+                Ok(error) => return Ok(error),
+                Err(inner) => Box::new(inner),
+            },
+            Err(error) => error,
+        };
+        Err(Self { error, ..self })
     }
 }
 
@@ -68,10 +211,14 @@ where
     E: Diagnostic + Send + Sync,
 {
     fn from(error: E) -> Self {
+        let mut details = DiagnosticDetails::capture(error.meta(), error.labels());
+        details.as_diagnostic = Some(as_diagnostic::<E>);
         Self {
-            meta: error.meta(),
             label: error.label(),
             help: error.help(),
+            severity: error.severity(),
+            url: error.url(),
+            details,
             error: Box::new(error),
         }
     }
@@ -79,6 +226,10 @@ where
 
 /**
 Optional additional metadata. This is used to improve diagnostic display, when present.
+
+`row` and `col` in [DiagnosticMetadata::Parse] are one-based, and `col` counts
+characters rather than bytes. [DiagnosticMetadata::ParseSpan] points at a
+byte range instead, and [LineIndex] converts between the two.
 */
 #[derive(Debug)]
 pub enum DiagnosticMetadata {
@@ -94,6 +245,33 @@ pub enum DiagnosticMetadata {
         col: usize,
         path: Option<PathBuf>,
     },
+    ParseSpan {
+        input: String,
+        span: SourceSpan,
+        path: Option<PathBuf>,
+    },
+}
+
+/**
+How serious a diagnostic is. Errors are rendered without a prefix, since
+they're usually printed right after `Error: `.
+*/
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Advice,
+    Warning,
+    #[default]
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Advice => write!(f, "advice"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Error => write!(f, "error"),
+        }
+    }
 }
 
 /**
@@ -105,10 +283,36 @@ pub trait Diagnostic: std::error::Error + Send + Sync + 'static {
     fn meta(&self) -> Option<DiagnosticMetadata> {
         None
     }
+    fn severity(&self) -> Severity {
+        Severity::Error
+    }
+    fn url(&self) -> Option<String> {
+        None
+    }
+    /**
+    Spans to underline in the source snippet, each with an optional message.
+    They're only drawn when [Diagnostic::meta] returns parse metadata, since
+    that's where the source comes from.
+    */
+    fn labels(&self) -> Vec<LabeledSpan> {
+        Vec::new()
+    }
+    /**
+    The diagnostic this one was caused by, if any, so its label and help
+    show up in the cause chain. The derive returns the `#[source]`,
+    `#[from]` or `ask` field here when that field is a [Diagnostic] itself.
+    */
+    fn diagnostic_source(&self) -> Option<&dyn Diagnostic> {
+        None
+    }
 }
 
 // This is needed so Box<dyn Diagnostic> is correctly treated as an Error.
-impl std::error::Error for Box<dyn Diagnostic> {}
+impl std::error::Error for Box<dyn Diagnostic> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        (**self).source()
+    }
+}
 
 pub trait IntoDiagnostic<T, E> {
     /**
@@ -126,11 +330,46 @@ pub trait IntoDiagnostic<T, E> {
 
 impl<T, E: std::error::Error + Send + Sync + 'static> IntoDiagnostic<T, E> for Result<T, E> {
     fn into_diagnostic(self, label: impl AsRef<str>) -> Result<T, DiagnosticError> {
-        self.map_err(|e| DiagnosticError {
-            error: Box::new(e),
-            label: label.as_ref().into(),
-            help: None,
-            meta: None,
+        self.map_err(|e| DiagnosticError::plain(Box::new(e), label.as_ref().into()))
+    }
+}
+
+pub trait WrapDiagnostic<T> {
+    /**
+    Adds a layer of context to an error as it bubbles up, e.g. what was
+    being done when it happened. The error itself is left as it is.
+
+    Implemented for [std::result::Result]s whose error converts into a
+    [DiagnosticError], including [DiagnosticResult]. Give other errors a
+    label with [IntoDiagnostic] first.
+
+    ### Example
+    ```ignore
+    std::fs::read("config.toml")
+        .into_diagnostic("mytool::config::read_failure")
+        .wrap_err("while reading config.toml")?;
+    load_profile(name).wrap_err(format!("while loading profile {}", name))?;
+    ```
+    */
+    fn wrap_err(self, msg: impl fmt::Display) -> DiagnosticResult<T>;
+
+    /**
+    Like [WrapDiagnostic::wrap_err], but only builds the message if there's
+    an error.
+    */
+    fn wrap_err_with<D: fmt::Display>(self, msg: impl FnOnce() -> D) -> DiagnosticResult<T>;
+}
+
+impl<T, E: Into<DiagnosticError>> WrapDiagnostic<T> for Result<T, E> {
+    fn wrap_err(self, msg: impl fmt::Display) -> DiagnosticResult<T> {
+        self.wrap_err_with(|| msg)
+    }
+
+    fn wrap_err_with<D: fmt::Display>(self, msg: impl FnOnce() -> D) -> DiagnosticResult<T> {
+        self.map_err(|err| {
+            let mut err = err.into();
+            err.details.context.push(msg().to_string());
+            err
         })
     }
 }
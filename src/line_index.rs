@@ -0,0 +1,78 @@
+/**
+Converts between byte offsets into some input and one-based row/col
+positions, where `col` counts characters. Line endings may be `\n` or
+`\r\n`.
+
+### Example
+```
+use thisdiagnostic::LineIndex;
+
+let index = LineIndex::new("a = 1\nb = ?\n");
+assert_eq!((2, 5), index.location(10));
+assert_eq!(Some(10), index.offset(2, 5));
+```
+*/
+#[derive(Debug, Clone)]
+pub struct LineIndex<'a> {
+    input: &'a str,
+    starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(input: &'a str) -> Self {
+        let starts = std::iter::once(0)
+            .chain(input.match_indices('\n').map(|(idx, _)| idx + 1))
+            .collect();
+        Self { input, starts }
+    }
+
+    /**
+    Number of lines in the input. A trailing newline starts a last, empty
+    line.
+    */
+    pub fn line_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /**
+    The text of the one-based `row`, without its line ending.
+    */
+    pub fn line(&self, row: usize) -> Option<&'a str> {
+        let start = *self.starts.get(row.checked_sub(1)?)?;
+        let end = self
+            .starts
+            .get(row)
+            .map_or(self.input.len(), |next| next - 1);
+        let line = &self.input[start..end];
+        Some(line.strip_suffix('\r').unwrap_or(line))
+    }
+
+    /**
+    One-based row and col of a byte offset. Offsets past the end of the
+    input are clamped to it, and offsets inside a character point at that
+    character.
+    */
+    pub fn location(&self, offset: usize) -> (usize, usize) {
+        let mut offset = offset.min(self.input.len());
+        while !self.input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let row = self.starts.partition_point(|&start| start <= offset);
+        let col = self.input[self.starts[row - 1]..offset].chars().count() + 1;
+        (row, col)
+    }
+
+    /**
+    Byte offset of a one-based row and col, or `None` if there's no such row.
+    Columns past the end of the line are clamped to it.
+    */
+    pub fn offset(&self, row: usize, col: usize) -> Option<usize> {
+        let line = self.line(row)?;
+        let start = self.starts[row - 1];
+        let idx = line
+            .char_indices()
+            .nth(col.saturating_sub(1))
+            .map_or(line.len(), |(idx, _)| idx);
+        Some(start + idx)
+    }
+}
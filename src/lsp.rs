@@ -0,0 +1,145 @@
+/*!
+Language Server Protocol shaped diagnostics, for editors that embed a parser
+built on this crate. Enabled with the `lsp` feature.
+
+Unlike the rest of this crate, positions here follow the LSP: lines are
+zero-based and characters count UTF-16 code units.
+*/
+
+use crate::{DiagnosticError, DiagnosticMetadata, LineIndex, Severity, SourceSpan};
+
+/**
+A diagnostic as sent in `textDocument/publishDiagnostics`.
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub range: Range,
+    pub severity: Option<DiagnosticSeverity>,
+    /// The diagnostic's label.
+    pub code: Option<String>,
+    /// The diagnostic's url, as the `href` of its `codeDescription`.
+    pub code_description: Option<String>,
+    pub source: Option<String>,
+    /// The error's message, followed by the help text, if any.
+    pub message: String,
+    pub related_information: Option<Vec<DiagnosticRelatedInformation>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DiagnosticSeverity {
+    Error = 1,
+    Warning = 2,
+    Information = 3,
+    Hint = 4,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticRelatedInformation {
+    pub location: Location,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub uri: String,
+    pub range: Range,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    /// Zero-based line.
+    pub line: u32,
+    /// Zero-based offset into the line, in UTF-16 code units.
+    pub character: u32,
+}
+
+impl Diagnostic {
+    /**
+    Converts a diagnostic with parse metadata, or returns `None` if it has
+    none. `uri` is the document the metadata's input came from, which
+    related information points into.
+
+    The range is that of the first primary labeled span, falling back to
+    the metadata's own location. Every other labeled span becomes related
+    information.
+    */
+    pub fn from_error(error: &DiagnosticError, uri: impl Into<String>) -> Option<Self> {
+        let (input, location) = match error.details.meta.as_ref()? {
+            DiagnosticMetadata::Parse {
+                input, row, col, ..
+            } => {
+                let index = LineIndex::new(input);
+                let offset = index
+                    .offset((*row).max(1), (*col).max(1))
+                    .unwrap_or(input.len());
+                let len = input[offset..].chars().next().map_or(0, char::len_utf8);
+                (input, SourceSpan::new(offset, len))
+            }
+            DiagnosticMetadata::ParseSpan { input, span, .. } => (input, *span),
+            _ => return None,
+        };
+        let index = LineIndex::new(input);
+
+        let primary = error.details.labels.iter().position(|label| label.primary);
+        let span = primary.map_or(location, |idx| error.details.labels[idx].span);
+
+        let uri = uri.into();
+        let related = error
+            .details
+            .labels
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| Some(*idx) != primary)
+            .map(|(_, label)| DiagnosticRelatedInformation {
+                location: Location {
+                    uri: uri.clone(),
+                    range: range(&index, input, label.span),
+                },
+                message: label.label.clone().unwrap_or_default(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut message = error.error.to_string();
+        if let Some(help) = &error.help {
+            message.push_str("\n\nhelp: ");
+            message.push_str(help);
+        }
+
+        Some(Self {
+            range: range(&index, input, span),
+            severity: Some(match error.severity {
+                Severity::Error => DiagnosticSeverity::Error,
+                Severity::Warning => DiagnosticSeverity::Warning,
+                Severity::Advice => DiagnosticSeverity::Hint,
+            }),
+            code: Some(error.label.clone()),
+            code_description: error.url.clone(),
+            source: None,
+            message,
+            related_information: (!related.is_empty()).then_some(related),
+        })
+    }
+}
+
+fn range(index: &LineIndex, input: &str, span: SourceSpan) -> Range {
+    Range {
+        start: position(index, input, span.offset),
+        end: position(index, input, span.end()),
+    }
+}
+
+fn position(index: &LineIndex, input: &str, offset: usize) -> Position {
+    let (row, col) = index.location(offset);
+    let start = index.offset(row, 1).unwrap_or(0);
+    let end = index.offset(row, col).unwrap_or(start);
+    Position {
+        line: (row - 1) as u32,
+        character: input[start..end].encode_utf16().count() as u32,
+    }
+}
@@ -0,0 +1,156 @@
+use std::fmt;
+
+use crate::chain;
+use crate::{DiagnosticError, DiagnosticMetadata, LineIndex, ReportHandler, Severity};
+
+/**
+Setting this to anything but `0` makes [DefaultReportHandler](crate::DefaultReportHandler)
+narrate diagnostics instead of drawing them.
+*/
+pub(crate) const NARRATE_ENV: &str = "THISDIAGNOSTIC_NARRATE";
+
+pub(crate) fn narrate_from_env() -> bool {
+    std::env::var_os(NARRATE_ENV).is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+/**
+Renders diagnostics as plain sentences, one per line, for screen readers.
+There are no colors, and source snippets are described rather than drawn:
+
+```text
+Error: mytool::config::duplicate_key.
+Diagnostic severity: error.
+Duplicate key.
+At file config.toml line 3 column 1.
+Problem at line 3 column 1: defined again here.
+Also see line 1 column 1: first defined here.
+Help: Remove one of them.
+```
+
+Install it with [set_hook](crate::set_hook), or set `THISDIAGNOSTIC_NARRATE=1`
+to have the default handler narrate instead.
+*/
+#[derive(Debug, Default, Clone)]
+pub struct NarratableReportHandler;
+
+impl NarratableReportHandler {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl ReportHandler for NarratableReportHandler {
+    fn debug(&self, error: &DiagnosticError, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match error.severity {
+            Severity::Error => "Error",
+            Severity::Warning => "Warning",
+            Severity::Advice => "Advice",
+        };
+        writeln!(f, "{}: {}", kind, sentence(&error.label))?;
+        writeln!(f, "Diagnostic severity: {}.", error.severity)?;
+        write!(f, "{}", sentence(&chain::headline(error)))?;
+
+        let input = match error.details.meta.as_ref() {
+            Some(DiagnosticMetadata::Net { url }) => {
+                write!(f, "\nAt {}.", url)?;
+                None
+            }
+            Some(DiagnosticMetadata::Fs { path }) => {
+                write!(f, "\nAt file {}.", path.display())?;
+                None
+            }
+            Some(DiagnosticMetadata::Parse {
+                input,
+                row,
+                col,
+                path,
+            }) => {
+                write_location(f, path.as_deref(), *row, *col)?;
+                Some(input)
+            }
+            Some(DiagnosticMetadata::ParseSpan { input, span, path }) => {
+                let (row, col) = LineIndex::new(input).location(span.offset);
+                write_location(f, path.as_deref(), row, col)?;
+                Some(input)
+            }
+            None => None,
+        };
+
+        if let Some(input) = input {
+            let index = LineIndex::new(input);
+            let labels = error
+                .details
+                .labels
+                .iter()
+                .filter(|label| label.primary)
+                .chain(error.details.labels.iter().filter(|label| !label.primary));
+            for label in labels {
+                let (row, col) = index.location(label.span.offset);
+                let prefix = if label.primary {
+                    "Problem at"
+                } else {
+                    "Also see"
+                };
+                write!(f, "\n{} line {} column {}", prefix, row, col)?;
+                match &label.label {
+                    Some(message) => write!(f, ": {}", sentence(message))?,
+                    None => write!(f, ".")?,
+                }
+            }
+        }
+
+        for cause in chain::collapsed_causes(error) {
+            let mut text = String::from("Caused by");
+            if let Some(diagnostic) = &cause.diagnostic {
+                text.push(' ');
+                text.push_str(&diagnostic.label);
+            }
+            if !cause.message.is_empty() {
+                text.push_str(": ");
+                text.push_str(&cause.message);
+            }
+            write!(f, "\n{}", sentence(&text))?;
+            if let Some(help) = cause.diagnostic.and_then(|diagnostic| diagnostic.help) {
+                write!(f, " Help: {}", sentence(&help))?;
+            }
+        }
+
+        if let Some(help) = &error.help {
+            write!(f, "\nHelp: {}", sentence(help))?;
+        }
+        if let Some(url) = &error.url {
+            write!(f, "\nFor more information see {}", url)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_location(
+    f: &mut fmt::Formatter<'_>,
+    path: Option<&std::path::Path>,
+    row: usize,
+    col: usize,
+) -> fmt::Result {
+    match path {
+        Some(path) => write!(
+            f,
+            "\nAt file {} line {} column {}.",
+            path.display(),
+            row,
+            col
+        ),
+        None => write!(f, "\nAt line {} column {}.", row, col),
+    }
+}
+
+/**
+Ends `text` with a period, unless it already ends with punctuation.
+*/
+fn sentence(text: &str) -> String {
+    let text = text.trim_end();
+    if text.ends_with(['.', '!', '?', ':']) {
+        text.to_string()
+    } else {
+        format!("{}.", text)
+    }
+}
@@ -0,0 +1,223 @@
+use crate::json::Json;
+use crate::{DiagnosticError, DiagnosticMetadata, LineIndex, Severity, SourceSpan};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/**
+Collects diagnostics into a single [SARIF 2.1.0](https://sarifweb.azurewebsites.net/)
+run, for GitHub code scanning and other SARIF viewers.
+
+Each distinct label becomes a rule, with the diagnostic's help and url as
+the rule's help. Each diagnostic becomes a result with a `level` of
+`"error"`, `"warning"` or `"note"`, and a location taken from its `Fs` or
+`Parse` metadata's path. Labeled spans become related locations. Columns
+count characters, as everywhere else in this crate.
+
+Viewers need every result to point at a file. Diagnostics without a path
+point at the [SarifReport::artifact_uri] instead, and are left out when
+there isn't one.
+
+### Example
+```
+use thisdiagnostic::{IntoDiagnostic, SarifReport};
+
+let err = "x".parse::<u8>().into_diagnostic("mytool::parse").unwrap_err();
+let sarif = SarifReport::new("mytool")
+    .version("1.0.0")
+    .artifact_uri("mytool.toml")
+    .render(&[err]);
+assert!(sarif.contains(r#""ruleId": "mytool::parse""#));
+```
+*/
+#[derive(Debug, Clone)]
+pub struct SarifReport {
+    name: String,
+    version: Option<String>,
+    information_uri: Option<String>,
+    artifact_uri: Option<String>,
+}
+
+impl SarifReport {
+    /**
+    A report for the tool called `name`.
+    */
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version: None,
+            information_uri: None,
+            artifact_uri: None,
+        }
+    }
+
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    pub fn information_uri(mut self, uri: impl Into<String>) -> Self {
+        self.information_uri = Some(uri.into());
+        self
+    }
+
+    /**
+    The file that diagnostics without a path of their own are reported
+    against, like the manifest or config file the tool was run on.
+    */
+    pub fn artifact_uri(mut self, uri: impl Into<String>) -> Self {
+        self.artifact_uri = Some(uri.into());
+        self
+    }
+
+    pub fn render<'a>(&self, errors: impl IntoIterator<Item = &'a DiagnosticError>) -> String {
+        let mut rules: Vec<&DiagnosticError> = Vec::new();
+        let mut results = Vec::new();
+        for error in errors {
+            let uri = match error_uri(error).or_else(|| self.artifact_uri.clone()) {
+                Some(uri) => uri,
+                None => continue,
+            };
+            let rule_index = match rules.iter().position(|rule| rule.label == error.label) {
+                Some(idx) => idx,
+                None => {
+                    rules.push(error);
+                    rules.len() - 1
+                }
+            };
+            results.push(result(error, rule_index, &uri));
+        }
+
+        let mut driver = vec![("name", self.name.as_str().into())];
+        if let Some(version) = &self.version {
+            driver.push(("version", version.as_str().into()));
+        }
+        if let Some(uri) = &self.information_uri {
+            driver.push(("informationUri", uri.as_str().into()));
+        }
+        driver.push(("rules", Json::Array(rules.into_iter().map(rule).collect())));
+
+        let log = Json::Object(vec![
+            ("$schema", SCHEMA.into()),
+            ("version", "2.1.0".into()),
+            (
+                "runs",
+                Json::Array(vec![Json::Object(vec![
+                    ("tool", Json::Object(vec![("driver", Json::Object(driver))])),
+                    ("columnKind", "unicodeCodePoints".into()),
+                    ("results", Json::Array(results)),
+                ])]),
+            ),
+        ]);
+
+        let mut out = String::new();
+        log.write_pretty(&mut out, 0)
+            .expect("writing to a String can't fail");
+        out
+    }
+}
+
+fn rule(error: &DiagnosticError) -> Json {
+    let mut rule = vec![("id", error.label.as_str().into())];
+    if let Some(help) = &error.help {
+        rule.push(("help", text(help)));
+    }
+    if let Some(url) = &error.url {
+        rule.push(("helpUri", url.as_str().into()));
+    }
+    Json::Object(rule)
+}
+
+/**
+The URI of the file `error`'s metadata points at, if any.
+*/
+fn error_uri(error: &DiagnosticError) -> Option<String> {
+    let path = match error.details.meta.as_ref()? {
+        DiagnosticMetadata::Fs { path } => path,
+        DiagnosticMetadata::Parse { path, .. } | DiagnosticMetadata::ParseSpan { path, .. } => {
+            path.as_ref()?
+        }
+        DiagnosticMetadata::Net { .. } => return None,
+    };
+    Some(path.to_string_lossy().replace('\\', "/"))
+}
+
+fn result(error: &DiagnosticError, rule_index: usize, uri: &str) -> Json {
+    let level = match error.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Advice => "note",
+    };
+    let mut result = vec![
+        ("ruleId", error.label.as_str().into()),
+        ("ruleIndex", rule_index.into()),
+        ("level", level.into()),
+        ("message", text(&error.error.to_string())),
+    ];
+
+    let (input, region) = match error.details.meta.as_ref() {
+        Some(DiagnosticMetadata::Parse {
+            input, row, col, ..
+        }) => {
+            let region = Json::Object(vec![
+                ("startLine", (*row).into()),
+                ("startColumn", (*col).into()),
+            ]);
+            (Some(input), Some(region))
+        }
+        Some(DiagnosticMetadata::ParseSpan { input, span, .. }) => {
+            let region = span_region(&LineIndex::new(input), *span);
+            (Some(input), Some(region))
+        }
+        Some(DiagnosticMetadata::Fs { .. } | DiagnosticMetadata::Net { .. }) | None => (None, None),
+    };
+
+    result.push(("locations", Json::Array(vec![location(uri, region, None)])));
+    if let (Some(input), false) = (input, error.details.labels.is_empty()) {
+        let index = LineIndex::new(input);
+        let related = error
+            .details
+            .labels
+            .iter()
+            .enumerate()
+            .map(|(idx, label)| {
+                let mut location = location(
+                    uri,
+                    Some(span_region(&index, label.span)),
+                    label.label.as_deref(),
+                );
+                if let Json::Object(entries) = &mut location {
+                    entries.insert(0, ("id", idx.into()));
+                }
+                location
+            })
+            .collect();
+        result.push(("relatedLocations", Json::Array(related)));
+    }
+
+    Json::Object(result)
+}
+
+fn location(uri: &str, region: Option<Json>, message: Option<&str>) -> Json {
+    let mut physical = vec![("artifactLocation", Json::Object(vec![("uri", uri.into())]))];
+    physical.extend(region.map(|region| ("region", region)));
+    let mut location = vec![("physicalLocation", Json::Object(physical))];
+    location.extend(message.map(|message| ("message", text(message))));
+    Json::Object(location)
+}
+
+fn span_region(index: &LineIndex, span: SourceSpan) -> Json {
+    let (start_line, start_column) = index.location(span.offset);
+    let (end_line, end_column) = index.location(span.end());
+    Json::Object(vec![
+        ("startLine", start_line.into()),
+        ("startColumn", start_column.into()),
+        ("endLine", end_line.into()),
+        ("endColumn", end_column.into()),
+        ("byteOffset", span.offset.into()),
+        ("byteLength", span.len.into()),
+    ])
+}
+
+fn text(message: &str) -> Json {
+    Json::Object(vec![("text", message.into())])
+}
@@ -0,0 +1,328 @@
+use std::cmp::Reverse;
+use std::fmt;
+
+use unicode_width::UnicodeWidthChar;
+
+use crate::style::{Painter, Style};
+use crate::{DiagnosticMetadata, LabeledSpan, LineIndex, SourceSpan};
+
+/// Lines of context shown above and below each underlined line.
+const CONTEXT_LINES: usize = 2;
+
+/// Tabs are expanded to this many spaces, both in the source and under it.
+const TAB_WIDTH: usize = 4;
+
+/**
+A rustc-style view of the input of a diagnostic's parse metadata: the lines
+around each labeled span with a line number gutter, and the spans underlined
+with their messages beneath. Spans whose context lines overlap share a
+snippet, spans further apart get snippets of their own. Spans covering
+several lines are underlined up to the end of their first line.
+
+Tabs, wide characters and CRLF line endings are accounted for when lining
+the underlines up.
+*/
+pub(crate) struct Snippet<'a> {
+    index: LineIndex<'a>,
+    spans: Vec<LabeledSpan>,
+}
+
+/**
+An underline under a single line, in display columns.
+*/
+struct Marker<'a> {
+    offset: usize,
+    width: usize,
+    primary: bool,
+    label: Option<&'a str>,
+}
+
+impl<'a> Snippet<'a> {
+    /**
+    The snippet for some parse metadata and its labels, or `None` if there is
+    nothing in range to underline. The metadata's own location is underlined
+    too, unless one of the labels is primary.
+    */
+    pub(crate) fn new(meta: &'a DiagnosticMetadata, labels: &[LabeledSpan]) -> Option<Self> {
+        let (input, index, location) = match meta {
+            DiagnosticMetadata::Parse {
+                input, row, col, ..
+            } => {
+                let index = LineIndex::new(input);
+                let location = index.offset((*row).max(1), (*col).max(1)).map(|offset| {
+                    let len = input[offset..].chars().next().map_or(0, char::len_utf8);
+                    SourceSpan::new(offset, len)
+                });
+                (input, index, location)
+            }
+            DiagnosticMetadata::ParseSpan { input, span, .. } => {
+                (input, LineIndex::new(input), Some(*span))
+            }
+            _ => return None,
+        };
+
+        let mut spans = labels.to_vec();
+        if !spans.iter().any(|span| span.primary) {
+            spans.extend(location.map(|span| LabeledSpan {
+                span,
+                label: None,
+                primary: true,
+            }));
+        }
+        spans.retain(|span| span.span.offset <= input.len());
+
+        (!spans.is_empty()).then_some(Self { index, spans })
+    }
+
+    pub(crate) fn render(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        primary: Style,
+        painter: Painter,
+    ) -> fmt::Result {
+        let mut rows = self
+            .spans
+            .iter()
+            .map(|span| self.index.location(span.span.offset).0)
+            .collect::<Vec<_>>();
+        rows.sort_unstable();
+        rows.dedup();
+
+        // Input ending in a newline has an empty last line after it, which
+        // is only worth showing when a span points there.
+        let mut line_count = self.index.line_count();
+        if line_count > 1 && self.index.line(line_count) == Some("") {
+            line_count -= 1;
+        }
+
+        let mut groups: Vec<(usize, usize)> = Vec::new();
+        for row in rows {
+            let first = row.saturating_sub(CONTEXT_LINES).max(1);
+            let last = (row + CONTEXT_LINES).min(line_count).max(row);
+            match groups.last_mut() {
+                Some(group) if first <= group.1 + 1 => group.1 = last,
+                _ => groups.push((first, last)),
+            }
+        }
+
+        let gutter = groups.last().map_or(1, |group| group.1.to_string().len());
+        for (first, last) in groups {
+            self.render_group(f, first, last, gutter, primary, painter)?;
+        }
+        Ok(())
+    }
+
+    fn render_group(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        first: usize,
+        last: usize,
+        gutter: usize,
+        primary: Style,
+        painter: Painter,
+    ) -> fmt::Result {
+        let gutter_style = painter.theme.styles.gutter;
+        let bar = painter.paint(gutter_style, painter.theme.glyphs.gutter);
+        let margin = format!("{:>width$} {}", "", bar, width = gutter);
+        writeln!(f, "{}", margin)?;
+        for number in first..=last {
+            let line = self.index.line(number).unwrap_or_default();
+            write!(
+                f,
+                "{} {}",
+                painter.paint(gutter_style, format!("{:>width$}", number, width = gutter)),
+                bar,
+            )?;
+            if line.is_empty() {
+                writeln!(f)?;
+            } else {
+                writeln!(f, " {}", expand_tabs(line))?;
+            }
+
+            let markers = self.markers(number, line);
+            for row in underline(&markers, primary, painter) {
+                writeln!(f, "{} {}", margin, row)?;
+            }
+        }
+        write!(f, "{}\n\n", margin)
+    }
+
+    fn markers(&self, row: usize, line: &str) -> Vec<Marker<'_>> {
+        self.spans
+            .iter()
+            .filter_map(|span| {
+                let (start_row, col) = self.index.location(span.span.offset);
+                if start_row != row {
+                    return None;
+                }
+                let (end_row, end_col) = self.index.location(span.span.end());
+                let end_col = if end_row == row { end_col } else { usize::MAX };
+                let (offset, width) = caret_position(line, col, end_col);
+                Some(Marker {
+                    offset,
+                    width,
+                    primary: span.primary,
+                    label: span.label.as_deref(),
+                })
+            })
+            .collect()
+    }
+}
+
+/**
+The rows drawn under a line: the underlines themselves, followed by the
+rightmost span's message, then the other messages hanging off connectors
+below. Messages from spans that start in the same column hang off the same
+connector.
+*/
+fn underline(markers: &[Marker], primary: Style, painter: Painter) -> Vec<String> {
+    if markers.is_empty() {
+        return Vec::new();
+    }
+    let (styles, glyphs) = (&painter.theme.styles, &painter.theme.glyphs);
+    let marker_style = |marker: &Marker| {
+        if marker.primary {
+            primary
+        } else {
+            styles.secondary
+        }
+    };
+
+    let width = markers
+        .iter()
+        .map(|marker| marker.offset + marker.width)
+        .max()
+        .unwrap_or(0);
+    // Narrower underlines go on top, so spans inside others stay visible.
+    // Primary ones win over secondary ones of the same width.
+    let mut layers = markers.iter().collect::<Vec<_>>();
+    layers.sort_by_key(|marker| (Reverse(marker.width), marker.primary));
+    let mut cells = vec![None; width];
+    for marker in layers {
+        cells[marker.offset..marker.offset + marker.width].fill(Some(marker.primary));
+    }
+
+    let mut first = Row::default();
+    let mut start = 0;
+    while start < cells.len() {
+        let end = cells[start..]
+            .iter()
+            .position(|cell| *cell != cells[start])
+            .map_or(cells.len(), |len| start + len);
+        let run = end - start;
+        match cells[start] {
+            Some(true) => {
+                let underline = glyphs.primary.to_string().repeat(run);
+                first.put(start, painter.paint(primary, underline), run)
+            }
+            Some(false) => {
+                let underline = glyphs.secondary.to_string().repeat(run);
+                first.put(start, painter.paint(styles.secondary, underline), run)
+            }
+            None => {}
+        }
+        start = end;
+    }
+
+    let rightmost = markers
+        .iter()
+        .max_by_key(|marker| (marker.offset + marker.width, marker.offset));
+    let mut hanging = markers
+        .iter()
+        .filter(|marker| marker.label.is_some())
+        .collect::<Vec<_>>();
+    if let Some(marker) = rightmost {
+        if let Some(label) = marker.label {
+            first.put(width + 1, painter.paint(marker_style(marker), label), 0);
+            hanging.retain(|other| !std::ptr::eq(*other, marker));
+        }
+    }
+    // Rows are built bottom up, so reversing first keeps messages that share
+    // a column in the order they were given, top to bottom.
+    hanging.reverse();
+    hanging.sort_by_key(|marker| marker.offset);
+
+    let mut rows = vec![first.text];
+    if hanging.is_empty() {
+        return rows;
+    }
+    // Messages hanging from the same column share one connector, and stack
+    // up under it.
+    let connectors = |row: &mut Row, markers: &[&Marker]| {
+        let mut last = None;
+        for marker in markers {
+            if last != Some(marker.offset) {
+                row.put(
+                    marker.offset,
+                    painter.paint(marker_style(marker), glyphs.connector),
+                    1,
+                );
+                last = Some(marker.offset);
+            }
+        }
+    };
+    let mut row = Row::default();
+    connectors(&mut row, &hanging);
+    rows.push(row.text);
+    for idx in (0..hanging.len()).rev() {
+        let mut row = Row::default();
+        let left = hanging[..idx]
+            .iter()
+            .take_while(|marker| marker.offset < hanging[idx].offset)
+            .copied()
+            .collect::<Vec<_>>();
+        connectors(&mut row, &left);
+        let marker = hanging[idx];
+        let label = marker.label.unwrap_or_default();
+        row.put(marker.offset, painter.paint(marker_style(marker), label), 0);
+        rows.push(row.text);
+    }
+    rows
+}
+
+/**
+A row of text built left to right out of pieces placed at display columns.
+*/
+#[derive(Default)]
+struct Row {
+    text: String,
+    width: usize,
+}
+
+impl Row {
+    fn put(&mut self, col: usize, piece: impl fmt::Display, width: usize) {
+        if col > self.width {
+            self.text.push_str(&" ".repeat(col - self.width));
+            self.width = col;
+        }
+        self.text.push_str(&piece.to_string());
+        self.width += width;
+    }
+}
+
+fn expand_tabs(line: &str) -> String {
+    line.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+fn char_width(c: char) -> usize {
+    if c == '\t' {
+        TAB_WIDTH
+    } else {
+        c.width().unwrap_or(0)
+    }
+}
+
+/**
+Display offset and width of the underline under the characters of `line`
+from `col` up to, but not including, `end_col`. Spans past the end of the
+line point just after it, and empty spans still get a single caret.
+*/
+fn caret_position(line: &str, col: usize, end_col: usize) -> (usize, usize) {
+    let mut chars = line.chars();
+    let offset = chars.by_ref().take(col - 1).map(char_width).sum();
+    let width: usize = chars
+        .take(end_col.saturating_sub(col))
+        .map(char_width)
+        .sum();
+    (offset, width.max(1))
+}
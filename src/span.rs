@@ -0,0 +1,87 @@
+use std::ops::Range;
+
+/**
+A range of bytes in a diagnostic's source input, as reported by most
+parsers.
+
+Spans can be built from a `(offset, len)` tuple or from a `Range<usize>`.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SourceSpan {
+    pub offset: usize,
+    pub len: usize,
+}
+
+impl SourceSpan {
+    pub fn new(offset: usize, len: usize) -> Self {
+        Self { offset, len }
+    }
+
+    /**
+    Byte offset just past the end of the span. Spans that run to the end of
+    the input, like `(offset, usize::MAX)`, end at `usize::MAX`.
+    */
+    pub fn end(&self) -> usize {
+        self.offset.saturating_add(self.len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl From<(usize, usize)> for SourceSpan {
+    fn from((offset, len): (usize, usize)) -> Self {
+        Self { offset, len }
+    }
+}
+
+impl From<Range<usize>> for SourceSpan {
+    fn from(range: Range<usize>) -> Self {
+        Self {
+            offset: range.start,
+            len: range.end.saturating_sub(range.start),
+        }
+    }
+}
+
+impl From<SourceSpan> for Range<usize> {
+    fn from(span: SourceSpan) -> Self {
+        span.offset..span.end()
+    }
+}
+
+/**
+A span with an optional message, drawn as an underline in the source snippet
+of a diagnostic. Offsets are into the `input` of the diagnostic's parse
+metadata.
+
+Primary spans are where the problem is, and secondary spans add context,
+like where something was first defined. They're underlined with the
+theme's [Glyphs::primary](crate::Glyphs::primary) and
+[Glyphs::secondary](crate::Glyphs::secondary) glyphs respectively.
+*/
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LabeledSpan {
+    pub span: SourceSpan,
+    pub label: Option<String>,
+    pub primary: bool,
+}
+
+impl LabeledSpan {
+    pub fn primary(span: impl Into<SourceSpan>, label: impl Into<String>) -> Self {
+        Self {
+            span: span.into(),
+            label: Some(label.into()),
+            primary: true,
+        }
+    }
+
+    pub fn secondary(span: impl Into<SourceSpan>, label: impl Into<String>) -> Self {
+        Self {
+            span: span.into(),
+            label: Some(label.into()),
+            primary: false,
+        }
+    }
+}
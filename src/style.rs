@@ -0,0 +1,135 @@
+use std::fmt;
+
+use crate::Theme;
+
+/**
+A terminal foreground color: one of the 16 standard ANSI colors, or an RGB
+color for terminals that support 24-bit color.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    BrightBlack,
+    BrightRed,
+    BrightGreen,
+    BrightYellow,
+    BrightBlue,
+    BrightMagenta,
+    BrightCyan,
+    BrightWhite,
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    /**
+    The SGR parameters that set this as the foreground color.
+    */
+    fn fg_code(self) -> String {
+        match self {
+            Color::Black => "30".into(),
+            Color::Red => "31".into(),
+            Color::Green => "32".into(),
+            Color::Yellow => "33".into(),
+            Color::Blue => "34".into(),
+            Color::Magenta => "35".into(),
+            Color::Cyan => "36".into(),
+            Color::White => "37".into(),
+            Color::BrightBlack => "90".into(),
+            Color::BrightRed => "91".into(),
+            Color::BrightGreen => "92".into(),
+            Color::BrightYellow => "93".into(),
+            Color::BrightBlue => "94".into(),
+            Color::BrightMagenta => "95".into(),
+            Color::BrightCyan => "96".into(),
+            Color::BrightWhite => "97".into(),
+            Color::Rgb(r, g, b) => format!("38;2;{};{};{}", r, g, b),
+        }
+    }
+}
+
+/**
+A color and text attributes for one element of a rendered diagnostic,
+written out as ANSI escape codes. This doesn't look at any global state;
+whether to color at all is up to the report handler.
+*/
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    pub fg: Option<Color>,
+    pub bold: bool,
+    pub underline: bool,
+}
+
+impl Style {
+    /**
+    A style that leaves text alone.
+    */
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn fg(color: Color) -> Self {
+        Self {
+            fg: Some(color),
+            ..Self::default()
+        }
+    }
+
+    pub fn bold(mut self) -> Self {
+        self.bold = true;
+        self
+    }
+
+    pub fn underline(mut self) -> Self {
+        self.underline = true;
+        self
+    }
+}
+
+/**
+Applies [Style]s to text, or leaves it alone when colors are disabled.
+*/
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Painter<'a> {
+    pub(crate) enabled: bool,
+    pub(crate) theme: &'a Theme,
+}
+
+impl Painter<'_> {
+    pub(crate) fn paint<T: fmt::Display>(self, style: Style, text: T) -> Painted<T> {
+        Painted {
+            style: if self.enabled { style } else { Style::new() },
+            text,
+        }
+    }
+}
+
+pub(crate) struct Painted<T> {
+    style: Style,
+    text: T,
+}
+
+impl<T: fmt::Display> fmt::Display for Painted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut codes = Vec::new();
+        if self.style.bold {
+            codes.push("1".into());
+        }
+        if self.style.underline {
+            codes.push("4".into());
+        }
+        if let Some(color) = self.style.fg {
+            codes.push(color.fg_code());
+        }
+        if codes.is_empty() {
+            return write!(f, "{}", self.text);
+        }
+        write!(f, "\x1b[{}m{}\x1b[0m", codes.join(";"), self.text)
+    }
+}
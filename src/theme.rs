@@ -0,0 +1,162 @@
+use crate::{Color, Severity, Style};
+
+/**
+How the default report handler draws diagnostics: the [Styles] of each
+element and the [Glyphs] snippets are drawn with.
+
+### Example
+```
+use thisdiagnostic::{ColorChoice, DefaultReportHandler, Theme};
+
+let handler = DefaultReportHandler::new()
+    .theme(Theme::high_contrast())
+    .color(ColorChoice::Always);
+thisdiagnostic::set_hook(Box::new(handler));
+```
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    pub styles: Styles,
+    pub glyphs: Glyphs,
+}
+
+impl Theme {
+    /**
+    Colors on a dark or light background, with Unicode glyphs. This is the
+    default.
+    */
+    pub fn new() -> Self {
+        Self {
+            styles: Styles {
+                error: Style::fg(Color::Red),
+                warning: Style::fg(Color::Yellow),
+                advice: Style::fg(Color::Blue),
+                secondary: Style::fg(Color::Blue),
+                gutter: Style::fg(Color::Blue).bold(),
+                link: Style::fg(Color::Cyan).underline(),
+                position: Style::fg(Color::Green),
+                help: Style::fg(Color::Yellow),
+            },
+            glyphs: Glyphs::unicode(),
+        }
+    }
+
+    /**
+    Bright, bold colors that stand out more.
+    */
+    pub fn high_contrast() -> Self {
+        Self {
+            styles: Styles {
+                error: Style::fg(Color::BrightRed).bold(),
+                warning: Style::fg(Color::BrightYellow).bold(),
+                advice: Style::fg(Color::BrightCyan).bold(),
+                secondary: Style::fg(Color::BrightCyan).bold(),
+                gutter: Style::fg(Color::BrightWhite).bold(),
+                link: Style::fg(Color::BrightCyan).bold().underline(),
+                position: Style::fg(Color::BrightGreen).bold(),
+                help: Style::fg(Color::BrightYellow).bold(),
+            },
+            glyphs: Glyphs::unicode(),
+        }
+    }
+
+    /**
+    No colors, only bold and underlined text.
+    */
+    pub fn monochrome() -> Self {
+        Self {
+            styles: Styles {
+                error: Style::new().bold(),
+                warning: Style::new().bold(),
+                advice: Style::new().bold(),
+                secondary: Style::new(),
+                gutter: Style::new(),
+                link: Style::new().underline(),
+                position: Style::new(),
+                help: Style::new().bold(),
+            },
+            glyphs: Glyphs::unicode(),
+        }
+    }
+
+    /**
+    Plain ASCII text, without any styling, for terminals that can't display
+    either.
+    */
+    pub fn ascii() -> Self {
+        Self {
+            styles: Styles::default(),
+            glyphs: Glyphs::ascii(),
+        }
+    }
+
+    pub(crate) fn severity(&self, severity: Severity) -> Style {
+        match severity {
+            Severity::Error => self.styles.error,
+            Severity::Warning => self.styles.warning,
+            Severity::Advice => self.styles.advice,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/**
+Styles for each element of a rendered diagnostic. The severity's style is
+used for the label and for primary underlines.
+*/
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Styles {
+    pub error: Style,
+    pub warning: Style,
+    pub advice: Style,
+    /// Secondary underlines and their messages.
+    pub secondary: Style,
+    /// Line numbers and the bar next to them.
+    pub gutter: Style,
+    /// Paths and urls.
+    pub link: Style,
+    /// Line and column numbers in the header.
+    pub position: Style,
+    /// The `help:` prefix.
+    pub help: Style,
+}
+
+/**
+Characters snippets are drawn with.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Glyphs {
+    /// Separates line numbers from the source.
+    pub gutter: char,
+    /// Underlines primary spans.
+    pub primary: char,
+    /// Underlines secondary spans.
+    pub secondary: char,
+    /// Connects underlines to messages hanging below them.
+    pub connector: char,
+}
+
+impl Glyphs {
+    pub fn unicode() -> Self {
+        Self {
+            gutter: '│',
+            primary: '━',
+            secondary: '─',
+            connector: '│',
+        }
+    }
+
+    pub fn ascii() -> Self {
+        Self {
+            gutter: '|',
+            primary: '^',
+            secondary: '-',
+            connector: '|',
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use tracing::Level;
+use tracing_error::{SpanTrace, SpanTraceStatus};
+
+use crate::{chain, DiagnosticError, DiagnosticMetadata, LineIndex, Severity};
+
+/**
+Captures the spans that are active right now, if a
+`tracing_error::ErrorLayer` is installed to keep track of them.
+*/
+pub(crate) fn capture() -> Option<SpanTrace> {
+    let span_trace = SpanTrace::capture();
+    (span_trace.status() == SpanTraceStatus::CAPTURED).then_some(span_trace)
+}
+
+/**
+One span in a span trace, with its recorded fields and where it was
+entered.
+*/
+pub(crate) struct Span {
+    pub(crate) name: String,
+    pub(crate) fields: String,
+    pub(crate) location: Option<String>,
+}
+
+/**
+The spans of `span_trace`, innermost first.
+*/
+pub(crate) fn spans(span_trace: &SpanTrace) -> Vec<Span> {
+    let mut spans = Vec::new();
+    span_trace.with_spans(|metadata, fields| {
+        spans.push(Span {
+            name: format!("{}::{}", metadata.target(), metadata.name()),
+            fields: fields.into(),
+            location: metadata.file().map(|file| match metadata.line() {
+                Some(line) => format!("{}:{}", file, line),
+                None => file.into(),
+            }),
+        });
+        true
+    });
+    spans
+}
+
+impl DiagnosticError {
+    /**
+    Emits this diagnostic as a `tracing` event, at `ERROR`, `WARN` or `INFO`
+    level depending on its severity. The message is the error's headline,
+    and the label, severity, help, url and metadata are recorded as fields.
+    Fields that don't apply, like `help` when there isn't any, are left out.
+
+    Only available with the `tracing` feature.
+
+    ### Example
+    ```ignore
+    if let Err(err) = load_profile(name) {
+        err.emit();
+    }
+    ```
+    */
+    pub fn emit(&self) {
+        let mut url = None;
+        let mut path = None;
+        let mut position = None;
+        match self.details.meta.as_ref() {
+            Some(DiagnosticMetadata::Net { url: meta_url }) => url = Some(meta_url.as_str()),
+            Some(DiagnosticMetadata::Fs { path: meta_path }) => path = Some(meta_path),
+            Some(DiagnosticMetadata::Parse {
+                row,
+                col,
+                path: meta_path,
+                ..
+            }) => {
+                path = meta_path.as_ref();
+                position = Some((*row, *col));
+            }
+            Some(DiagnosticMetadata::ParseSpan {
+                input,
+                span,
+                path: meta_path,
+            }) => {
+                path = meta_path.as_ref();
+                position = Some(LineIndex::new(input).location(span.offset));
+            }
+            None => {}
+        }
+        let path = path.map(|path| path.display());
+        let row = position.map(|(row, _)| row as u64);
+        let col = position.map(|(_, col)| col as u64);
+        let headline = chain::headline(self);
+
+        // Event levels have to be constants, hence a callsite per severity.
+        macro_rules! emit {
+            ($level:expr) => {
+                tracing::event!(
+                    $level,
+                    label = %self.label,
+                    severity = %self.severity,
+                    help = self.help.as_deref(),
+                    url = self.url.as_deref(),
+                    meta.url = url,
+                    meta.path = path.as_ref().map(tracing::field::display),
+                    meta.row = row,
+                    meta.col = col,
+                    "{}",
+                    headline
+                )
+            };
+        }
+        match self.severity {
+            Severity::Error => emit!(Level::ERROR),
+            Severity::Warning => emit!(Level::WARN),
+            Severity::Advice => emit!(Level::INFO),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use thisdiagnostic::{
+    ColorChoice, DefaultReportHandler, DiagnosticResult, IntoDiagnostic, ReportHandler, Theme,
+};
+
+#[inline(never)]
+fn load() -> DiagnosticResult<()> {
+    Err(std::io::Error::other("permission denied")).into_diagnostic("backtrace::load")
+}
+
+// Backtrace::capture only reads the environment once, so everything that
+// depends on it stays in this one test.
+#[test]
+fn captured_and_filtered() {
+    std::env::set_var("RUST_LIB_BACKTRACE", "1");
+    let err = load().unwrap_err();
+    assert!(err.details.backtrace.is_some());
+
+    let handler = DefaultReportHandler::new()
+        .color(ColorChoice::Never)
+        .theme(Theme::ascii());
+    let rendered = handler.render(&err);
+    let (message, backtrace) = rendered.split_once("\n\nBacktrace:\n").unwrap();
+    assert_eq!("backtrace::load\n\npermission denied", message);
+    assert!(backtrace.starts_with("    0: backtrace::load\n"));
+    assert!(backtrace.contains("tests/backtrace.rs:"));
+    assert!(backtrace.contains(": backtrace::captured_and_filtered\n"));
+    for frame in backtrace
+        .lines()
+        .filter(|line| !line.trim().starts_with("at "))
+    {
+        let symbol = frame.split_once(": ").unwrap().1;
+        assert!(!symbol.starts_with("std::"), "{}", frame);
+        assert!(!symbol.starts_with("core::"), "{}", frame);
+        assert!(!symbol.starts_with("thisdiagnostic::"), "{}", frame);
+        assert!(
+            !symbol.contains("__rust_begin_short_backtrace"),
+            "{}",
+            frame
+        );
+    }
+
+    let hidden = handler.backtrace(false).render(&err);
+    assert_eq!("backtrace::load\n\npermission denied", hidden);
+}
@@ -0,0 +1,181 @@
+use thisdiagnostic::{
+    ColorChoice, DefaultReportHandler, Diagnostic, DiagnosticError, IntoDiagnostic,
+    JsonReportHandler, NarratableReportHandler, ReportHandler, Theme,
+};
+use thiserror::Error;
+
+fn plain() -> DefaultReportHandler {
+    DefaultReportHandler::new()
+        .color(ColorChoice::Never)
+        .theme(Theme::ascii())
+        .backtrace(false)
+}
+
+#[derive(Debug, Error)]
+#[error("Failed to load config.")]
+pub struct LoadError {
+    #[source]
+    cause: ReadError,
+}
+
+#[derive(Debug, Error)]
+pub enum ReadError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+}
+
+#[test]
+fn io_chain() {
+    let err: Result<(), _> = Err(LoadError {
+        cause: ReadError::Io(std::io::Error::other("permission denied")),
+    });
+    let err = err.into_diagnostic("chain::load").unwrap_err();
+    assert_eq!(
+        "chain::load\n\n\
+         Failed to load config.\n\n\
+         Caused by:\n    \
+         0: permission denied",
+        plain().render(&err)
+    );
+}
+
+#[derive(Debug, Error)]
+#[error("Request failed.")]
+pub struct RequestError {
+    #[source]
+    cause: DiagnosticError,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Not a valid port:\n{0}")]
+#[diagnostic(label = "chain::port", help = "Ports go up to 65535.")]
+pub struct PortError(String);
+
+#[test]
+fn diagnostic_causes() {
+    let inner: Result<(), _> = Err(LoadError {
+        cause: ReadError::Io(std::io::Error::other("permission denied")),
+    });
+    let inner = inner.into_diagnostic("chain::load").unwrap_err();
+    let err: Result<(), _> = Err(RequestError { cause: inner });
+    let err = err.into_diagnostic("chain::request").unwrap_err();
+    assert_eq!(
+        "chain::request\n\n\
+         Request failed.\n\n\
+         Caused by:\n    \
+         0: chain::load\n       \
+            Failed to load config.\n    \
+         1: permission denied",
+        plain().render(&err)
+    );
+
+    let err: Result<(), _> = Err(BoxedCause(Box::new(PortError("70000".into()))));
+    let err = err.into_diagnostic("chain::request").unwrap_err();
+    assert_eq!(
+        "chain::request\n\n\
+         Request failed.\n\n\
+         Caused by:\n    \
+         0: chain::port\n       \
+            Not a valid port:\n       \
+            70000\n       \
+            help: Ports go up to 65535.",
+        plain().render(&err)
+    );
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Invalid config.")]
+#[diagnostic(label = "chain::config")]
+pub struct ConfigError {
+    #[source]
+    port: PortError,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum StartError {
+    #[error("Couldn't start.")]
+    #[diagnostic(label = "chain::start")]
+    Config(#[from] ConfigError),
+}
+
+#[test]
+fn derived_causes() {
+    let err: DiagnosticError = StartError::from(ConfigError {
+        port: PortError("70000".into()),
+    })
+    .into();
+    assert_eq!(
+        "chain::start\n\n\
+         Couldn't start.\n\n\
+         Caused by:\n    \
+         0: chain::config\n       \
+            Invalid config.\n    \
+         1: chain::port\n       \
+            Not a valid port:\n       \
+            70000\n       \
+            help: Ports go up to 65535.",
+        plain().render(&err)
+    );
+}
+
+#[derive(Debug, Error)]
+#[error("Request failed.")]
+pub struct BoxedCause(#[source] Box<dyn Diagnostic>);
+
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct Same(#[source] std::io::Error);
+
+#[derive(Debug, Error)]
+#[error("Outer.")]
+pub struct Outer(#[source] Same);
+
+#[test]
+fn repeated_messages() {
+    let err: Result<(), _> = Err(Outer(Same(std::io::Error::other("Inner."))));
+    let err = err.into_diagnostic("chain::repeated").unwrap_err();
+    assert_eq!(
+        "chain::repeated\n\nOuter.\n\nCaused by:\n    0: Inner.",
+        plain().render(&err)
+    );
+
+    let err: Result<(), _> = Err(Same(std::io::Error::other("Inner.")));
+    let err = err.into_diagnostic("chain::repeated").unwrap_err();
+    assert_eq!("chain::repeated\n\nInner.", plain().render(&err));
+}
+
+#[test]
+fn many_causes() {
+    let mut err: Box<dyn std::error::Error + Send + Sync> = Box::new(std::io::Error::other("0"));
+    for n in 1..=11 {
+        err = Box::new(Layer(n, err));
+    }
+    let err: Result<(), _> = Err(Layer(12, err));
+    let err = err.into_diagnostic("chain::many").unwrap_err();
+    let rendered = plain().render(&err);
+    assert!(rendered.contains("\n     0: 11\n     1: 10\n"));
+    assert!(rendered.ends_with("\n    10: 1\n    11: 0"));
+}
+
+#[derive(Debug, Error)]
+#[error("{0}")]
+pub struct Layer(usize, #[source] Box<dyn std::error::Error + Send + Sync>);
+
+#[test]
+fn other_handlers() {
+    let inner: Result<(), _> = Err(std::io::Error::other("permission denied"));
+    let inner = inner.into_diagnostic("chain::read").unwrap_err();
+    let err: Result<(), _> = Err(RequestError { cause: inner });
+    let err = err.into_diagnostic("chain::request").unwrap_err();
+
+    assert_eq!(
+        "Error: chain::request.\n\
+         Diagnostic severity: error.\n\
+         Request failed.\n\
+         Caused by chain::read: permission denied.",
+        NarratableReportHandler::new().render(&err)
+    );
+
+    let json = JsonReportHandler::new().line_delimited(true).render(&err);
+    assert!(json.contains(r#""causes":["permission denied"]"#));
+}
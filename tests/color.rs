@@ -0,0 +1,58 @@
+use thisdiagnostic::{ColorChoice, DefaultReportHandler, IntoDiagnostic, ReportHandler};
+
+fn render(handler: DefaultReportHandler) -> String {
+    let err: Result<(), _> = Err(std::io::Error::other("Oops."));
+    let mut err = err.into_diagnostic("color::oops").unwrap_err();
+    err.help = Some("Try again.".into());
+    handler.render(&err)
+}
+
+#[test]
+fn explicit_choices() {
+    assert_eq!(
+        "color::oops\n\nOops.\n\nhelp: Try again.",
+        render(
+            DefaultReportHandler::new()
+                .color(ColorChoice::Never)
+                .backtrace(false)
+        )
+    );
+    assert_eq!(
+        "\x1b[31mcolor::oops\x1b[0m\n\nOops.\n\n\x1b[33mhelp\x1b[0m: Try again.",
+        render(
+            DefaultReportHandler::new()
+                .color(ColorChoice::Always)
+                .backtrace(false)
+        )
+    );
+}
+
+// The environment is global, so this is a single test to keep it from racing.
+#[test]
+fn environment() {
+    let auto = || ColorChoice::Auto.should_color();
+    let stderr_is_terminal = {
+        std::env::remove_var("NO_COLOR");
+        std::env::remove_var("CLICOLOR");
+        std::env::remove_var("CLICOLOR_FORCE");
+        auto()
+    };
+
+    std::env::set_var("CLICOLOR_FORCE", "1");
+    assert!(auto());
+    std::env::set_var("NO_COLOR", "1");
+    assert!(!auto());
+    std::env::set_var("NO_COLOR", "");
+    assert!(auto());
+
+    std::env::set_var("CLICOLOR_FORCE", "0");
+    assert_eq!(stderr_is_terminal, auto());
+    std::env::set_var("CLICOLOR", "0");
+    assert!(!auto());
+
+    std::env::remove_var("NO_COLOR");
+    std::env::remove_var("CLICOLOR");
+    std::env::remove_var("CLICOLOR_FORCE");
+    assert!(ColorChoice::Always.should_color());
+    assert!(!ColorChoice::Never.should_color());
+}
@@ -0,0 +1,30 @@
+/*!
+Helpers shared by the integration tests. Each test crate only uses some of
+them.
+*/
+#![allow(dead_code)]
+
+use thisdiagnostic::{
+    ColorChoice, DefaultReportHandler, DiagnosticError, IntoDiagnostic, ReportHandler, Theme,
+};
+
+/**
+A [DiagnosticError] with the given label, wrapping an `io::Error` with the
+given message.
+*/
+pub fn error(label: &str, message: &str) -> DiagnosticError {
+    let err: Result<(), _> = Err(std::io::Error::other(message.to_string()));
+    err.into_diagnostic(label).unwrap_err()
+}
+
+/**
+`err` as the default handler renders it, without colors, backtraces or
+non-ASCII glyphs.
+*/
+pub fn render(err: &DiagnosticError) -> String {
+    let handler = DefaultReportHandler::new()
+        .color(ColorChoice::Never)
+        .theme(Theme::ascii())
+        .backtrace(false);
+    handler.render(err)
+}
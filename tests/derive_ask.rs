@@ -0,0 +1,109 @@
+use thisdiagnostic::{Diagnostic, DiagnosticMetadata, LabeledSpan};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+#[error("Unexpected token.")]
+pub struct ParseFailure;
+
+impl Diagnostic for ParseFailure {
+    fn label(&self) -> String {
+        "ask::parse".into()
+    }
+
+    fn help(&self) -> Option<String> {
+        Some("Check your syntax.".into())
+    }
+
+    fn meta(&self) -> Option<DiagnosticMetadata> {
+        Some(DiagnosticMetadata::Parse {
+            input: "a = ".into(),
+            row: 1,
+            col: 5,
+            path: None,
+        })
+    }
+
+    fn labels(&self) -> Vec<LabeledSpan> {
+        vec![LabeledSpan::primary(4..4, "expected a value")]
+    }
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error(transparent)]
+pub struct Newtype(#[diagnostic(ask)] ParseFailure);
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Config error.")]
+pub struct Config {
+    path: String,
+    #[diagnostic(ask)]
+    source: ParseFailure,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Wrapper error.")]
+pub enum Wrapper {
+    Named {
+        path: String,
+        #[diagnostic(ask)]
+        inner: ParseFailure,
+    },
+    Second(String, #[diagnostic(ask)] ParseFailure),
+    #[diagnostic(label = "ask::overridden")]
+    Overridden(#[diagnostic(ask)] ParseFailure),
+    #[diagnostic(label = "ask::plain")]
+    Plain,
+}
+
+fn assert_parse_meta(meta: Option<DiagnosticMetadata>) {
+    match meta {
+        Some(DiagnosticMetadata::Parse { row, col, .. }) => {
+            assert_eq!(1, row);
+            assert_eq!(5, col);
+        }
+        other => panic!("expected parse metadata, got {:?}", other),
+    }
+}
+
+#[test]
+fn struct_fields() {
+    let newtype = Newtype(ParseFailure);
+    assert_eq!("ask::parse", newtype.label());
+    assert_eq!("Check your syntax.", newtype.help().unwrap());
+    assert_parse_meta(newtype.meta());
+
+    let config = Config {
+        path: "config.toml".into(),
+        source: ParseFailure,
+    };
+    assert_eq!("ask::parse", config.label());
+    assert_eq!("Check your syntax.", config.help().unwrap());
+    assert_parse_meta(config.meta());
+    assert_eq!(1, config.labels().len());
+    assert_eq!("config.toml", config.path);
+}
+
+#[test]
+fn variant_fields() {
+    let named = Wrapper::Named {
+        path: "config.toml".into(),
+        inner: ParseFailure,
+    };
+    assert_eq!("ask::parse", named.label());
+    assert_eq!("Check your syntax.", named.help().unwrap());
+    assert_parse_meta(named.meta());
+
+    let second = Wrapper::Second("config.toml".into(), ParseFailure);
+    assert_eq!("ask::parse", second.label());
+    assert_parse_meta(second.meta());
+    assert_eq!(1, second.labels().len());
+
+    let overridden = Wrapper::Overridden(ParseFailure);
+    assert_eq!("ask::overridden", overridden.label());
+    assert_eq!("Check your syntax.", overridden.help().unwrap());
+
+    let plain = Wrapper::Plain;
+    assert_eq!("ask::plain", plain.label());
+    assert!(plain.meta().is_none());
+    assert!(plain.labels().is_empty());
+}
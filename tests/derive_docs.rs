@@ -0,0 +1,65 @@
+use thisdiagnostic::Diagnostic;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[diagnostic(help_from_docs)]
+pub enum ApiError {
+    /// An API key is required.
+    #[error("Endpoint operation requires an API key.")]
+    #[diagnostic(label = "docs::needs_api_key")]
+    NeedsApiKey,
+
+    /// Unexpected   response from
+    ///     the server.
+    ///
+    /// This is likely a bug with the server API.
+    #[error("Unexpected or undocumented response.")]
+    #[diagnostic(label = "docs::bad_response")]
+    BadResponse,
+
+    /// Overridden by the explicit help.
+    #[error("Request timed out.")]
+    #[diagnostic(label = "docs::timeout")]
+    #[diagnostic(help = "Try again later.")]
+    Timeout,
+
+    #[error("Undocumented.")]
+    #[diagnostic(label = "docs::undocumented")]
+    Undocumented,
+}
+
+/// Documented, but not opted in.
+#[derive(Debug, Error, Diagnostic)]
+#[error("Plain error.")]
+#[diagnostic(label = "docs::plain")]
+pub struct Plain;
+
+/**
+Block doc comments
+work too.
+*/
+#[derive(Debug, Error, Diagnostic)]
+#[error("Block error.")]
+#[diagnostic(label = "docs::block")]
+#[diagnostic(help_from_docs)]
+pub struct Block;
+
+#[test]
+fn variant_docs() {
+    assert_eq!(
+        "An API key is required.",
+        ApiError::NeedsApiKey.help().unwrap()
+    );
+    assert_eq!(
+        "Unexpected response from the server.\n\nThis is likely a bug with the server API.",
+        ApiError::BadResponse.help().unwrap()
+    );
+    assert_eq!("Try again later.", ApiError::Timeout.help().unwrap());
+    assert!(ApiError::Undocumented.help().is_none());
+}
+
+#[test]
+fn struct_docs() {
+    assert!(Plain.help().is_none());
+    assert_eq!("Block doc comments work too.", Block.help().unwrap());
+}
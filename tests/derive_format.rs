@@ -0,0 +1,81 @@
+use thisdiagnostic::Diagnostic;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Unknown command.")]
+#[diagnostic(label = "format::unknown_command")]
+#[diagnostic(help = "did you mean `{suggestion}`?")]
+pub struct UnknownCommand {
+    command: String,
+    suggestion: String,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Lookup error.")]
+pub enum Lookup {
+    #[diagnostic(label = "format::missing::{0}")]
+    #[diagnostic(help = "No entry named `{0}`. Try {1:?}.")]
+    Missing(String, String),
+    #[diagnostic(label = "format::shadowed")]
+    #[diagnostic(help = "`{name}` shadows `{name}` from line {line}. {{Braces}} stay.")]
+    Shadowed { name: String, line: usize },
+}
+
+#[test]
+fn struct_fields() {
+    let err = UnknownCommand {
+        command: "isntall".into(),
+        suggestion: "install".into(),
+    };
+    assert_eq!("format::unknown_command", err.label());
+    assert_eq!("did you mean `install`?", err.help().unwrap());
+    assert_eq!("isntall", err.command);
+}
+
+#[test]
+fn tuple_variant_fields() {
+    let err = Lookup::Missing("foo".into(), "bar".into());
+    assert_eq!("format::missing::foo", err.label());
+    assert_eq!("No entry named `foo`. Try \"bar\".", err.help().unwrap());
+}
+
+#[test]
+fn named_variant_fields() {
+    let err = Lookup::Shadowed {
+        name: "x".into(),
+        line: 3,
+    };
+    assert_eq!("format::shadowed", err.label());
+    assert_eq!(
+        "`x` shadows `x` from line 3. {Braces} stay.",
+        err.help().unwrap()
+    );
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Bad value.")]
+pub enum BadValue {
+    #[diagnostic(label = "format::bad_type", help = "Expected {r#type:?}.")]
+    Type { r#type: String },
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Bad value.")]
+#[diagnostic(label = "format::bad_{r#type}", help = "Expected {r#type:?}.")]
+pub struct BadType {
+    r#type: String,
+}
+
+#[test]
+fn raw_identifiers() {
+    let err = BadType {
+        r#type: "int".into(),
+    };
+    assert_eq!("format::bad_int", err.label());
+    assert_eq!("Expected \"int\".", err.help().unwrap());
+
+    let err = BadValue::Type {
+        r#type: "str".into(),
+    };
+    assert_eq!("Expected \"str\".", err.help().unwrap());
+}
@@ -0,0 +1,55 @@
+use std::fmt::Debug;
+
+use thisdiagnostic::Diagnostic;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Inner error.")]
+#[diagnostic(label = "generic::inner")]
+#[diagnostic(help = "Inner.")]
+pub struct Inner;
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum ParseError<T: std::error::Error> {
+    #[error("Bad input: {0}")]
+    #[diagnostic(label = "generic::bad_input")]
+    #[diagnostic(help = "Bad input.")]
+    BadInput(T),
+    #[error(transparent)]
+    Wrapped(#[diagnostic(ask)] T),
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Borrowed error: {name}")]
+#[diagnostic(label = "generic::borrowed")]
+#[diagnostic(help = "Borrowed.")]
+pub struct Borrowed<'a, T: Debug> {
+    name: &'a str,
+    value: T,
+}
+
+fn assert_diagnostic<D: Diagnostic>(_: &D) {}
+
+#[test]
+fn generic_enum() {
+    let bad = ParseError::BadInput(Inner);
+    assert_diagnostic(&bad);
+    assert_eq!("generic::bad_input", bad.label());
+    assert_eq!("Bad input.", bad.help().unwrap());
+
+    let wrapped: ParseError<Inner> = ParseError::Wrapped(Inner);
+    assert_eq!("generic::inner", wrapped.label());
+    assert_eq!("Inner.", wrapped.help().unwrap());
+}
+
+#[test]
+fn generic_struct_with_lifetime() {
+    let borrowed = Borrowed {
+        name: "static",
+        value: 1,
+    };
+    assert_diagnostic(&borrowed);
+    assert_eq!("generic::borrowed", borrowed.label());
+    assert_eq!("Borrowed.", borrowed.help().unwrap());
+    assert_eq!(1, borrowed.value);
+}
@@ -0,0 +1,68 @@
+use thisdiagnostic::Diagnostic;
+use thiserror::Error;
+
+mod api {
+    use thisdiagnostic::Diagnostic;
+    use thiserror::Error;
+
+    #[derive(Debug, Error, Diagnostic)]
+    pub enum ApiError {
+        #[error("Endpoint operation requires an API key.")]
+        NeedsApiKey,
+        #[error("Unexpected response.")]
+        #[diagnostic(label = "mytool::api::unexpected_response")]
+        BadResponse,
+    }
+
+    #[derive(Debug, Error, Diagnostic)]
+    #[error("Timed out.")]
+    pub struct Timeout;
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[diagnostic(label(prefix = "mytool::config"))]
+pub enum ConfigError {
+    #[error("Missing key.")]
+    #[diagnostic(label = "missing_key::{0}")]
+    MissingKey(String),
+    #[error("Invalid config.")]
+    Invalid,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Read failure.")]
+#[diagnostic(label(prefix = "mytool::fs"))]
+#[diagnostic(label = "read")]
+pub struct ReadFailure;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Write failure.")]
+#[diagnostic(label(prefix = "mytool::fs"))]
+pub struct WriteFailure;
+
+#[test]
+fn default_labels() {
+    assert_eq!(
+        "derive_label::api::ApiError::NeedsApiKey",
+        api::ApiError::NeedsApiKey.label()
+    );
+    assert_eq!(
+        "mytool::api::unexpected_response",
+        api::ApiError::BadResponse.label()
+    );
+    assert_eq!("derive_label::api::Timeout", api::Timeout.label());
+}
+
+#[test]
+fn prefixed_labels() {
+    assert_eq!(
+        "mytool::config::missing_key::name",
+        ConfigError::MissingKey("name".into()).label()
+    );
+    assert_eq!(
+        "mytool::config::ConfigError::Invalid",
+        ConfigError::Invalid.label()
+    );
+    assert_eq!("mytool::fs::read", ReadFailure.label());
+    assert_eq!("mytool::fs::WriteFailure", WriteFailure.label());
+}
@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+
+use thisdiagnostic::{Diagnostic, DiagnosticError, DiagnosticMetadata, SourceSpan};
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Filesystem error.")]
+pub enum FsError {
+    #[diagnostic(label = "meta::fs::read")]
+    Read {
+        #[diagnostic(meta(path))]
+        path: PathBuf,
+    },
+    #[diagnostic(label = "meta::fs::write")]
+    Write(#[diagnostic(meta(path))] String),
+    #[diagnostic(label = "meta::fs::other")]
+    Other,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Request failed.")]
+#[diagnostic(label = "meta::net::request")]
+pub struct HttpError {
+    #[diagnostic(meta(url))]
+    url: String,
+    status: u16,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Syntax error.")]
+#[diagnostic(label = "meta::parse::syntax")]
+pub struct SyntaxError {
+    #[diagnostic(meta(input))]
+    source_text: String,
+    #[diagnostic(meta(row))]
+    line: usize,
+    #[diagnostic(meta(col))]
+    column: u32,
+    #[diagnostic(meta(path))]
+    file: PathBuf,
+}
+
+#[test]
+fn fs_metadata() {
+    let read = FsError::Read {
+        path: "config.toml".into(),
+    };
+    match read.meta() {
+        Some(DiagnosticMetadata::Fs { path }) => assert_eq!(PathBuf::from("config.toml"), path),
+        other => panic!("expected fs metadata, got {:?}", other),
+    }
+
+    let write = FsError::Write("out.txt".into());
+    match write.meta() {
+        Some(DiagnosticMetadata::Fs { path }) => assert_eq!(PathBuf::from("out.txt"), path),
+        other => panic!("expected fs metadata, got {:?}", other),
+    }
+
+    assert!(FsError::Other.meta().is_none());
+
+    let rendered = format!("{:?}", DiagnosticError::from(read));
+    assert!(rendered.contains("meta::fs::read"));
+    assert!(rendered.contains(" @ "));
+    assert!(rendered.contains("config.toml"));
+}
+
+#[test]
+fn net_metadata() {
+    let err = HttpError {
+        url: "https://example.com".into(),
+        status: 404,
+    };
+    match err.meta() {
+        Some(DiagnosticMetadata::Net { url }) => assert_eq!("https://example.com", url),
+        other => panic!("expected net metadata, got {:?}", other),
+    }
+    assert_eq!(404, err.status);
+}
+
+#[test]
+fn parse_metadata() {
+    let err = SyntaxError {
+        source_text: "a = ".into(),
+        line: 1,
+        column: 5,
+        file: "config.toml".into(),
+    };
+    match err.meta() {
+        Some(DiagnosticMetadata::Parse {
+            input,
+            row,
+            col,
+            path,
+        }) => {
+            assert_eq!("a = ", input);
+            assert_eq!(1, row);
+            assert_eq!(5, col);
+            assert_eq!(Some(PathBuf::from("config.toml")), path);
+        }
+        other => panic!("expected parse metadata, got {:?}", other),
+    }
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Unexpected token.")]
+#[diagnostic(label = "meta::parse::token")]
+pub struct TokenError {
+    #[diagnostic(meta(input))]
+    source_text: String,
+    #[diagnostic(meta(span))]
+    token: std::ops::Range<usize>,
+}
+
+#[test]
+fn span_metadata() {
+    let err = TokenError {
+        source_text: "a = 1\nb = ???\n".into(),
+        token: 10..13,
+    };
+    match err.meta() {
+        Some(DiagnosticMetadata::ParseSpan { input, span, path }) => {
+            assert_eq!("a = 1\nb = ???\n", input);
+            assert_eq!(SourceSpan::new(10, 3), span);
+            assert_eq!(None, path);
+        }
+        other => panic!("expected span metadata, got {:?}", other),
+    }
+}
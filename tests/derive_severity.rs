@@ -0,0 +1,79 @@
+use thisdiagnostic::{Diagnostic, DiagnosticError, Severity};
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Deprecated option.")]
+#[diagnostic(label = "severity::deprecated", severity = "warning")]
+pub struct Deprecated;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Lint failed.")]
+#[diagnostic(severity = "advice")]
+pub enum Lint {
+    #[diagnostic(label = "severity::style")]
+    Style,
+    #[diagnostic(label = "severity::unused", severity = "warning")]
+    Unused,
+    #[diagnostic(label = "severity::broken", severity = "error")]
+    Broken,
+    Wrapped(#[diagnostic(ask)] Deprecated),
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Plain error.")]
+#[diagnostic(label = "severity::plain")]
+pub struct Plain;
+
+#[test]
+fn defaults_to_error() {
+    assert_eq!(Severity::Error, Plain.severity());
+    assert_eq!(Severity::Error, Severity::default());
+}
+
+#[test]
+fn derived_severity() {
+    assert_eq!(Severity::Warning, Deprecated.severity());
+    assert_eq!(Severity::Advice, Lint::Style.severity());
+    assert_eq!(Severity::Warning, Lint::Unused.severity());
+    assert_eq!(Severity::Error, Lint::Broken.severity());
+    assert_eq!(Severity::Warning, Lint::Wrapped(Deprecated).severity());
+}
+
+#[test]
+fn rendered_prefix() {
+    let warning = DiagnosticError::from(Deprecated);
+    assert_eq!(Severity::Warning, warning.severity);
+    assert!(format!("{:?}", warning).contains("warning: severity::deprecated"));
+
+    let advice = DiagnosticError::from(Lint::Style);
+    assert!(format!("{:?}", advice).contains("advice: severity::style"));
+
+    let error = DiagnosticError::from(Plain);
+    let rendered = format!("{:?}", error);
+    assert!(rendered.contains("severity::plain"));
+    assert!(!rendered.contains("error: "));
+}
+
+// Deliberately sticks to the deprecated bare attribute.
+#[allow(deprecated)]
+mod bare {
+    use thisdiagnostic::{Diagnostic, Severity};
+    use thiserror::Error;
+
+    #[derive(Debug, Error, Diagnostic)]
+    #[error("Lint failed.")]
+    #[severity("advice")]
+    pub enum Lint {
+        #[label("severity::bare::style")]
+        Style,
+        #[label("severity::bare::unused")]
+        #[severity("warning")]
+        Unused,
+    }
+
+    #[test]
+    fn bare_attribute() {
+        assert_eq!(Severity::Advice, Lint::Style.severity());
+        assert_eq!(Severity::Warning, Lint::Unused.severity());
+    }
+}
@@ -1,3 +1,6 @@
+// Deliberately sticks to the deprecated bare attributes.
+#![allow(deprecated)]
+
 use thisdiagnostic::Diagnostic;
 use thiserror::Error;
 
@@ -0,0 +1,98 @@
+use thisdiagnostic::{Diagnostic, DiagnosticError};
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Missing file.")]
+#[diagnostic(
+    label = "url::missing_file",
+    url = "https://docs.example/{label}?file={path}"
+)]
+pub struct MissingFile {
+    path: String,
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Api error.")]
+#[diagnostic(url = "https://docs.example/{label}")]
+pub enum ApiError {
+    #[diagnostic(label = "url::needs_api_key")]
+    NeedsApiKey,
+    #[diagnostic(label = "url::bad_status", url = "https://http.example/status/{0}")]
+    BadStatus(u16),
+    Wrapped(#[diagnostic(ask)] MissingFile),
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Plain error.")]
+#[diagnostic(label = "url::plain")]
+pub struct Plain;
+
+#[test]
+fn struct_url() {
+    let err = MissingFile {
+        path: "config.toml".into(),
+    };
+    assert_eq!(
+        "https://docs.example/url::missing_file?file=config.toml",
+        err.url().unwrap()
+    );
+    assert!(Plain.url().is_none());
+}
+
+#[test]
+fn enum_url() {
+    assert_eq!(
+        "https://docs.example/url::needs_api_key",
+        ApiError::NeedsApiKey.url().unwrap()
+    );
+    assert_eq!(
+        "https://http.example/status/404",
+        ApiError::BadStatus(404).url().unwrap()
+    );
+    let wrapped = ApiError::Wrapped(MissingFile {
+        path: "a.toml".into(),
+    });
+    assert_eq!(
+        "https://docs.example/url::missing_file?file=a.toml",
+        wrapped.url().unwrap()
+    );
+}
+
+#[test]
+fn rendered_footer() {
+    let err = DiagnosticError::from(ApiError::NeedsApiKey);
+    assert_eq!(
+        Some("https://docs.example/url::needs_api_key"),
+        err.url.as_deref()
+    );
+    let rendered = format!("{:?}", err);
+    assert!(rendered.contains("for more information see"));
+    assert!(rendered.contains("https://docs.example/url::needs_api_key"));
+}
+
+// Deliberately sticks to the deprecated bare attribute.
+#[allow(deprecated)]
+mod bare {
+    use thisdiagnostic::Diagnostic;
+    use thiserror::Error;
+
+    #[derive(Debug, Error, Diagnostic)]
+    #[error("Critical error.")]
+    #[url("https://docs.example/{label}")]
+    pub enum Critical {
+        #[label("url::bare::orange")]
+        Orange,
+        #[label("url::bare::red")]
+        #[url("https://docs.example/red")]
+        Red,
+    }
+
+    #[test]
+    fn bare_attribute() {
+        assert_eq!(
+            "https://docs.example/url::bare::orange",
+            Critical::Orange.url().unwrap()
+        );
+        assert_eq!("https://docs.example/red", Critical::Red.url().unwrap());
+    }
+}
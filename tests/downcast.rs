@@ -0,0 +1,83 @@
+use thisdiagnostic::{
+    Diagnostic, DiagnosticError, DiagnosticResult, IntoDiagnostic, WrapDiagnostic,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Profile {0} not found.")]
+#[diagnostic(label = "downcast::not_found")]
+pub struct NotFound(String);
+
+#[derive(Debug, Error)]
+#[error("Couldn't load the config.")]
+pub struct LoadFailed(#[source] std::io::Error);
+
+fn not_found() -> DiagnosticResult<()> {
+    Err(NotFound("dev".into()).into())
+}
+
+#[test]
+fn top_level() {
+    let err = not_found().unwrap_err();
+    assert!(err.is::<NotFound>());
+    assert!(!err.is::<std::io::Error>());
+    assert_eq!("dev", err.downcast_ref::<NotFound>().unwrap().0);
+}
+
+#[test]
+fn through_context() {
+    let err = not_found()
+        .wrap_err("while loading profile dev")
+        .wrap_err("while starting up")
+        .unwrap_err();
+    assert!(err.is::<NotFound>());
+    assert_eq!(2, err.details.context.len());
+}
+
+#[test]
+fn through_source_chain() {
+    let err: DiagnosticResult<()> = Err(LoadFailed(std::io::Error::other("permission denied")))
+        .into_diagnostic("downcast::load");
+    let err = err.unwrap_err();
+    assert!(err.is::<LoadFailed>());
+    let io = err.downcast_ref::<std::io::Error>().unwrap();
+    assert_eq!("permission denied", io.to_string());
+}
+
+#[test]
+fn through_nested_diagnostic() {
+    let err = not_found().into_diagnostic("downcast::outer").unwrap_err();
+    assert!(err.is::<DiagnosticError>());
+    assert!(err.is::<NotFound>());
+}
+
+#[test]
+fn mutable() {
+    let mut err = not_found().unwrap_err();
+    err.downcast_mut::<NotFound>().unwrap().0.push_str("-2");
+    assert_eq!("dev-2", err.downcast_ref::<NotFound>().unwrap().0);
+    assert!(err.downcast_mut::<std::io::Error>().is_none());
+
+    let mut nested = not_found().into_diagnostic("downcast::outer").unwrap_err();
+    assert!(nested.downcast_mut::<NotFound>().is_some());
+}
+
+#[test]
+fn by_value() {
+    let err = not_found().wrap_err("while loading").unwrap_err();
+    let err = err.downcast::<std::io::Error>().unwrap_err();
+    assert_eq!("downcast::not_found", err.label);
+    assert_eq!(vec!["while loading".to_string()], err.details.context);
+    let not_found = err.downcast::<NotFound>().unwrap();
+    assert_eq!("dev", not_found.0);
+}
+
+#[test]
+fn by_value_nested() {
+    let err = not_found().into_diagnostic("downcast::outer").unwrap_err();
+    let err = err.downcast::<std::io::Error>().unwrap_err();
+    assert_eq!("downcast::outer", err.label);
+    assert!(err.is::<NotFound>());
+    let not_found = err.downcast::<NotFound>().unwrap();
+    assert_eq!("dev", not_found.0);
+}
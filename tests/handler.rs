@@ -0,0 +1,49 @@
+use std::fmt;
+
+use thisdiagnostic::{
+    ColorChoice, DefaultReportHandler, DiagnosticError, IntoDiagnostic, ReportHandler, Severity,
+};
+
+struct Terse;
+
+impl ReportHandler for Terse {
+    fn debug(&self, error: &DiagnosticError, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} [{}]: {}", error.severity, error.label, error.error)
+    }
+}
+
+fn error() -> DiagnosticError {
+    let err: Result<(), DiagnosticError> = Err(fmt::Error).into_diagnostic("handler::oops");
+    let mut err = err.unwrap_err();
+    err.severity = Severity::Warning;
+    err.help = Some("Try again.".into());
+    err
+}
+
+// Hooks are global, so this is a single test to keep them from racing.
+#[test]
+fn hooks() {
+    let plain = || {
+        Box::new(
+            DefaultReportHandler::new()
+                .color(ColorChoice::Never)
+                .backtrace(false),
+        )
+    };
+    thisdiagnostic::set_hook(plain());
+    let default = "warning: handler::oops\n\n\
+                   an error occurred when formatting an argument\n\n\
+                   help: Try again.";
+    assert_eq!(default, format!("{:?}", error()));
+    assert_eq!(default, error().to_string());
+
+    thisdiagnostic::set_hook(Box::new(Terse));
+    assert_eq!(
+        "warning [handler::oops]: an error occurred when formatting an argument",
+        format!("{:?}", error())
+    );
+    assert_eq!("Error", format!("{:#?}", error()));
+
+    thisdiagnostic::set_hook(plain());
+    assert_eq!(default, format!("{:?}", error()));
+}
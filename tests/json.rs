@@ -0,0 +1,130 @@
+use std::path::PathBuf;
+
+use thisdiagnostic::{
+    Diagnostic, DiagnosticError, DiagnosticMetadata, JsonReportHandler, LabeledSpan, ReportHandler,
+    Severity,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+#[error("Unexpected \"token\".")]
+pub struct TokenError {
+    #[source]
+    cause: std::io::Error,
+}
+
+impl Diagnostic for TokenError {
+    fn label(&self) -> String {
+        "json::token".into()
+    }
+
+    fn help(&self) -> Option<String> {
+        Some("Remove it.".into())
+    }
+
+    fn meta(&self) -> Option<DiagnosticMetadata> {
+        Some(DiagnosticMetadata::ParseSpan {
+            input: "a = 1\nb = ?\n".into(),
+            span: (10..11).into(),
+            path: Some(PathBuf::from("config.toml")),
+        })
+    }
+
+    fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn labels(&self) -> Vec<LabeledSpan> {
+        vec![LabeledSpan::primary(10..11, "here")]
+    }
+}
+
+fn token_error() -> DiagnosticError {
+    TokenError {
+        cause: std::io::Error::other("read\tfailed"),
+    }
+    .into()
+}
+
+#[test]
+fn line_delimited() {
+    let json = JsonReportHandler::new()
+        .line_delimited(true)
+        .render(&token_error());
+    assert_eq!(
+        concat!(
+            r#"{"label":"json::token","message":"Unexpected \"token\".","#,
+            r#""severity":"warning","help":"Remove it.","url":null,"#,
+            r#""metadata":{"kind":"parse","path":"config.toml","row":2,"col":5,"offset":10,"len":1},"#,
+            r#""context":[],"causes":["read\tfailed"],"#,
+            r#""spans":[{"offset":10,"len":1,"row":2,"col":5,"label":"here","primary":true}]}"#,
+        ),
+        json
+    );
+}
+
+#[test]
+fn write_all() {
+    let errors = [token_error(), token_error()];
+    let handler = JsonReportHandler::new().line_delimited(true);
+    let mut out = Vec::new();
+    handler.write_all(&mut out, &errors).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let record = handler.render(&errors[0]);
+    assert_eq!(format!("{}\n{}\n", record, record), out);
+    assert_eq!(2, out.lines().count());
+}
+
+#[test]
+fn pretty() {
+    let mut err = token_error();
+    err.details.meta = Some(DiagnosticMetadata::Fs {
+        path: "config.toml".into(),
+    });
+    err.details.labels.clear();
+    assert_eq!(
+        r#"{
+  "label": "json::token",
+  "message": "Unexpected \"token\".",
+  "severity": "warning",
+  "help": "Remove it.",
+  "url": null,
+  "metadata": {
+    "kind": "fs",
+    "path": "config.toml"
+  },
+  "context": [],
+  "causes": [
+    "read\tfailed"
+  ],
+  "spans": []
+}"#,
+        JsonReportHandler::new().render(&err)
+    );
+}
+
+#[test]
+fn spans_without_input() {
+    let mut err = token_error();
+    err.details.meta = Some(DiagnosticMetadata::Net {
+        url: "https://example.com".into(),
+    });
+    let json = JsonReportHandler::new().line_delimited(true).render(&err);
+    assert!(json.contains(r#""metadata":{"kind":"net","url":"https://example.com"}"#));
+    assert!(json.contains(r#""row":null,"col":null"#));
+}
+
+#[test]
+fn row_col_metadata() {
+    let mut err = token_error();
+    err.details.meta = Some(DiagnosticMetadata::Parse {
+        input: "a".into(),
+        row: 1,
+        col: 2,
+        path: None,
+    });
+    let json = JsonReportHandler::new().line_delimited(true).render(&err);
+    assert!(json.contains(
+        r#""metadata":{"kind":"parse","path":null,"row":1,"col":2,"offset":null,"len":null}"#
+    ));
+}
@@ -0,0 +1,229 @@
+use thisdiagnostic::{
+    ColorChoice, DefaultReportHandler, Diagnostic, DiagnosticError, DiagnosticMetadata,
+    LabeledSpan, SourceSpan, Theme,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+#[error("Duplicate key.")]
+pub struct DuplicateKey {
+    input: String,
+    first: SourceSpan,
+    second: SourceSpan,
+}
+
+impl Diagnostic for DuplicateKey {
+    fn label(&self) -> String {
+        "labels::duplicate_key".into()
+    }
+
+    fn help(&self) -> Option<String> {
+        None
+    }
+
+    fn meta(&self) -> Option<DiagnosticMetadata> {
+        Some(DiagnosticMetadata::ParseSpan {
+            input: self.input.clone(),
+            span: self.second,
+            path: None,
+        })
+    }
+
+    fn labels(&self) -> Vec<LabeledSpan> {
+        vec![
+            LabeledSpan::secondary(self.first, "first defined here"),
+            LabeledSpan::primary(self.second, "defined again here"),
+        ]
+    }
+}
+
+fn render(err: impl Diagnostic) -> String {
+    thisdiagnostic::set_hook(Box::new(
+        DefaultReportHandler::new()
+            .color(ColorChoice::Never)
+            .theme(Theme::ascii())
+            .backtrace(false),
+    ));
+    format!("{:?}", DiagnosticError::from(err))
+}
+
+#[test]
+fn default_labels() {
+    let err: DiagnosticError = DuplicateKey {
+        input: String::new(),
+        first: (0, 0).into(),
+        second: (0, 0).into(),
+    }
+    .into();
+    assert_eq!(2, err.details.labels.len());
+}
+
+#[test]
+fn same_snippet() {
+    let input = "name = 1\nversion = 2\nname = 3\n";
+    let rendered = render(DuplicateKey {
+        input: input.into(),
+        first: SourceSpan::new(0, 4),
+        second: SourceSpan::new(21, 4),
+    });
+    assert_eq!(
+        "labels::duplicate_key - line: 3, col: 1\n\
+         \n  |\
+         \n1 | name = 1\
+         \n  | ---- first defined here\
+         \n2 | version = 2\
+         \n3 | name = 3\
+         \n  | ^^^^ defined again here\
+         \n  |\
+         \n\nDuplicate key.",
+        rendered
+    );
+}
+
+#[test]
+fn separate_snippets() {
+    let mut input = String::from("name = 1\n");
+    for n in 0..10 {
+        input.push_str(&format!("key{} = {}\n", n, n));
+    }
+    let second = input.len();
+    input.push_str("name = 2\n");
+    let rendered = render(DuplicateKey {
+        input,
+        first: SourceSpan::new(0, 4),
+        second: SourceSpan::new(second, 4),
+    });
+    assert_eq!(
+        "labels::duplicate_key - line: 12, col: 1\n\
+         \n   |\
+         \n 1 | name = 1\
+         \n   | ---- first defined here\
+         \n 2 | key0 = 0\
+         \n 3 | key1 = 1\
+         \n   |\
+         \n\
+         \n   |\
+         \n10 | key8 = 8\
+         \n11 | key9 = 9\
+         \n12 | name = 2\
+         \n   | ^^^^ defined again here\
+         \n   |\
+         \n\nDuplicate key.",
+        rendered
+    );
+}
+
+#[test]
+fn same_line() {
+    let rendered = render(DuplicateKey {
+        input: "{ a = 1, b = 2, a = 3 }".into(),
+        first: SourceSpan::new(2, 1),
+        second: SourceSpan::new(16, 1),
+    });
+    assert!(rendered.contains(
+        "\n1 | { a = 1, b = 2, a = 3 }\
+         \n  |   -             ^ defined again here\
+         \n  |   |\
+         \n  |   first defined here\n"
+    ));
+}
+
+#[derive(Debug, Error)]
+#[error("Three spans.")]
+pub struct ThreeSpans;
+
+impl Diagnostic for ThreeSpans {
+    fn label(&self) -> String {
+        "labels::three".into()
+    }
+
+    fn help(&self) -> Option<String> {
+        None
+    }
+
+    fn meta(&self) -> Option<DiagnosticMetadata> {
+        Some(DiagnosticMetadata::Parse {
+            input: "f(a, b, c)".into(),
+            row: 1,
+            col: 3,
+            path: None,
+        })
+    }
+
+    fn labels(&self) -> Vec<LabeledSpan> {
+        vec![
+            LabeledSpan::secondary(2..3, "one"),
+            LabeledSpan::secondary(5..6, "two"),
+            LabeledSpan::secondary(8..9, "three"),
+        ]
+    }
+}
+
+#[test]
+fn hanging_labels() {
+    let rendered = render(ThreeSpans);
+    assert!(rendered.contains(
+        "\n1 | f(a, b, c)\
+         \n  |   ^  -  - three\
+         \n  |   |  |\
+         \n  |   |  two\
+         \n  |   one\n"
+    ));
+}
+
+#[derive(Debug, Error)]
+#[error("Overlapping spans.")]
+pub struct Overlapping(Vec<LabeledSpan>);
+
+impl Diagnostic for Overlapping {
+    fn label(&self) -> String {
+        "labels::overlapping".into()
+    }
+
+    fn help(&self) -> Option<String> {
+        None
+    }
+
+    fn meta(&self) -> Option<DiagnosticMetadata> {
+        Some(DiagnosticMetadata::Parse {
+            input: "let foo = bar;".into(),
+            row: 1,
+            col: 5,
+            path: None,
+        })
+    }
+
+    fn labels(&self) -> Vec<LabeledSpan> {
+        self.0.clone()
+    }
+}
+
+#[test]
+fn nested_spans() {
+    let rendered = render(Overlapping(vec![
+        LabeledSpan::primary(4..7, "outer"),
+        LabeledSpan::secondary(4..5, "inner"),
+    ]));
+    assert!(rendered.contains(
+        "\n1 | let foo = bar;\
+         \n  |     -^^ outer\
+         \n  |     |\
+         \n  |     inner\n"
+    ));
+}
+
+#[test]
+fn same_offset() {
+    let rendered = render(Overlapping(vec![
+        LabeledSpan::primary(4..5, "first"),
+        LabeledSpan::secondary(4..7, "second"),
+        LabeledSpan::secondary(10..13, "right"),
+    ]));
+    assert!(rendered.contains(
+        "\n1 | let foo = bar;\
+         \n  |     ^--   --- right\
+         \n  |     |\
+         \n  |     first\
+         \n  |     second\n"
+    ));
+}
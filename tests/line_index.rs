@@ -0,0 +1,67 @@
+use thisdiagnostic::{LineIndex, SourceSpan};
+
+#[test]
+fn lines() {
+    let index = LineIndex::new("a = 1\r\n\nb = 2\n");
+    assert_eq!(4, index.line_count());
+    assert_eq!(Some("a = 1"), index.line(1));
+    assert_eq!(Some(""), index.line(2));
+    assert_eq!(Some("b = 2"), index.line(3));
+    assert_eq!(Some(""), index.line(4));
+    assert_eq!(None, index.line(0));
+    assert_eq!(None, index.line(5));
+}
+
+#[test]
+fn location() {
+    let index = LineIndex::new("a = 1\nb = 2\n");
+    assert_eq!((1, 1), index.location(0));
+    assert_eq!((1, 5), index.location(4));
+    assert_eq!((1, 6), index.location(5));
+    assert_eq!((2, 1), index.location(6));
+    assert_eq!((3, 1), index.location(12));
+    assert_eq!((3, 1), index.location(100));
+}
+
+#[test]
+fn location_counts_characters() {
+    let index = LineIndex::new("名前 = 値");
+    assert_eq!((1, 2), index.location(3));
+    assert_eq!((1, 2), index.location(4));
+    assert_eq!((1, 6), index.location(9));
+}
+
+#[test]
+fn offset() {
+    let index = LineIndex::new("a = 1\r\n名前 = 値\n");
+    assert_eq!(Some(0), index.offset(1, 1));
+    assert_eq!(Some(4), index.offset(1, 5));
+    assert_eq!(Some(5), index.offset(1, 50));
+    assert_eq!(Some(10), index.offset(2, 2));
+    assert_eq!(Some(7 + "名前 = 値".len()), index.offset(2, 7));
+    assert_eq!(None, index.offset(0, 1));
+    assert_eq!(None, index.offset(4, 1));
+}
+
+#[test]
+fn round_trip() {
+    let input = "fn main() {\n\tlet 名前 = \"値\";\r\n}\n";
+    let index = LineIndex::new(input);
+    for (offset, _) in input
+        .char_indices()
+        .filter(|(_, c)| *c != '\r' && *c != '\n')
+    {
+        let (row, col) = index.location(offset);
+        assert_eq!(Some(offset), index.offset(row, col));
+    }
+}
+
+#[test]
+fn spans() {
+    assert_eq!(SourceSpan::new(3, 4), SourceSpan::from(3..7));
+    assert_eq!(SourceSpan::new(3, 4), SourceSpan::from((3, 4)));
+    assert_eq!(7, SourceSpan::new(3, 4).end());
+    assert_eq!(usize::MAX, SourceSpan::new(4, usize::MAX).end());
+    assert!(SourceSpan::from(5..5).is_empty());
+    assert_eq!(3..7, std::ops::Range::from(SourceSpan::new(3, 4)));
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "lsp")]
+
+use thisdiagnostic::lsp::{
+    Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, Position, Range,
+};
+use thisdiagnostic::{DiagnosticError, DiagnosticMetadata, IntoDiagnostic, LabeledSpan, Severity};
+
+fn error(meta: DiagnosticMetadata) -> DiagnosticError {
+    let err: Result<(), _> = Err(std::io::Error::other("Unexpected token."));
+    let mut err = err.into_diagnostic("lsp::token").unwrap_err();
+    err.details.meta = Some(meta);
+    err
+}
+
+fn range(start: (u32, u32), end: (u32, u32)) -> Range {
+    Range {
+        start: Position {
+            line: start.0,
+            character: start.1,
+        },
+        end: Position {
+            line: end.0,
+            character: end.1,
+        },
+    }
+}
+
+#[test]
+fn row_col() {
+    let mut err = error(DiagnosticMetadata::Parse {
+        input: "a = 1\nb = ?\n".into(),
+        row: 2,
+        col: 5,
+        path: None,
+    });
+    err.severity = Severity::Warning;
+    err.help = Some("Use a number.".into());
+    err.url = Some("https://example.com/token".into());
+    assert_eq!(
+        Some(Diagnostic {
+            range: range((1, 4), (1, 5)),
+            severity: Some(DiagnosticSeverity::Warning),
+            code: Some("lsp::token".into()),
+            code_description: Some("https://example.com/token".into()),
+            source: None,
+            message: "Unexpected token.\n\nhelp: Use a number.".into(),
+            related_information: None,
+        }),
+        Diagnostic::from_error(&err, "file:///config.toml")
+    );
+}
+
+#[test]
+fn utf16_columns() {
+    // '𝒳' is two UTF-16 code units, 'é' is one.
+    let input = "é𝒳 = ?";
+    let err = error(DiagnosticMetadata::ParseSpan {
+        input: input.into(),
+        span: (input.find('?').unwrap(), 1).into(),
+        path: None,
+    });
+    let diagnostic = Diagnostic::from_error(&err, "file:///config.toml").unwrap();
+    assert_eq!(range((0, 6), (0, 7)), diagnostic.range);
+}
+
+#[test]
+fn related_information() {
+    let mut err = error(DiagnosticMetadata::ParseSpan {
+        input: "a = 1\r\na = 2\r\n".into(),
+        span: (7..8).into(),
+        path: None,
+    });
+    err.details.labels = vec![
+        LabeledSpan::secondary(0..1, "first defined here"),
+        LabeledSpan::primary(7..8, "defined again here"),
+    ];
+    let diagnostic = Diagnostic::from_error(&err, "file:///config.toml").unwrap();
+    assert_eq!(range((1, 0), (1, 1)), diagnostic.range);
+    assert_eq!(
+        Some(vec![DiagnosticRelatedInformation {
+            location: Location {
+                uri: "file:///config.toml".into(),
+                range: range((0, 0), (0, 1)),
+            },
+            message: "first defined here".into(),
+        }]),
+        diagnostic.related_information
+    );
+}
+
+#[test]
+fn needs_parse_metadata() {
+    let err = error(DiagnosticMetadata::Fs {
+        path: "config.toml".into(),
+    });
+    assert!(Diagnostic::from_error(&err, "file:///config.toml").is_none());
+}
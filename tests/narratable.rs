@@ -0,0 +1,72 @@
+mod common;
+
+use common::error;
+use thisdiagnostic::{
+    ColorChoice, DefaultReportHandler, DiagnosticMetadata, LabeledSpan, NarratableReportHandler,
+    ReportHandler, Severity,
+};
+
+#[test]
+fn plain() {
+    let mut err = error(
+        "mytool::api::needs_api_key",
+        "Endpoint operation requires an API key",
+    );
+    err.help = Some("Please supply an API key.".into());
+    err.url = Some("https://example.com/api-keys".into());
+    assert_eq!(
+        "Error: mytool::api::needs_api_key.\n\
+         Diagnostic severity: error.\n\
+         Endpoint operation requires an API key.\n\
+         Help: Please supply an API key.\n\
+         For more information see https://example.com/api-keys",
+        NarratableReportHandler::new().render(&err)
+    );
+}
+
+#[test]
+fn locations() {
+    let mut err = error("mytool::config::duplicate_key", "Duplicate key.");
+    err.severity = Severity::Warning;
+    err.details.meta = Some(DiagnosticMetadata::ParseSpan {
+        input: "a = 1\nb = 2\na = 3\n".into(),
+        span: (12..13).into(),
+        path: Some("config.toml".into()),
+    });
+    err.details.labels = vec![
+        LabeledSpan::secondary(0..1, "first defined here"),
+        LabeledSpan::primary(12..13, "defined again here"),
+    ];
+    assert_eq!(
+        "Warning: mytool::config::duplicate_key.\n\
+         Diagnostic severity: warning.\n\
+         Duplicate key.\n\
+         At file config.toml line 3 column 1.\n\
+         Problem at line 3 column 1: defined again here.\n\
+         Also see line 1 column 1: first defined here.",
+        NarratableReportHandler::new().render(&err)
+    );
+
+    err.details.meta = Some(DiagnosticMetadata::Fs {
+        path: "config.toml".into(),
+    });
+    assert!(NarratableReportHandler::new()
+        .render(&err)
+        .ends_with("Duplicate key.\nAt file config.toml."));
+}
+
+#[test]
+fn environment() {
+    let err = error("mytool::oops", "Oops!");
+    let handler = DefaultReportHandler::new().color(ColorChoice::Always);
+
+    std::env::set_var("THISDIAGNOSTIC_NARRATE", "1");
+    assert_eq!(
+        "Error: mytool::oops.\nDiagnostic severity: error.\nOops!",
+        handler.render(&err)
+    );
+
+    std::env::set_var("THISDIAGNOSTIC_NARRATE", "0");
+    assert!(handler.render(&err).starts_with("\x1b[31mmytool::oops"));
+    std::env::remove_var("THISDIAGNOSTIC_NARRATE");
+}
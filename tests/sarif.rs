@@ -0,0 +1,167 @@
+mod common;
+
+use common::error;
+use thisdiagnostic::{DiagnosticMetadata, LabeledSpan, SarifReport, Severity};
+
+#[test]
+fn full_log() {
+    let mut missing = error("lint::missing", "File is missing.");
+    missing.details.meta = Some(DiagnosticMetadata::Fs {
+        path: "src/lib.rs".into(),
+    });
+    missing.help = Some("Create it.".into());
+    missing.url = Some("https://example.com/missing".into());
+
+    let mut unused = error("lint::unused", "Unused key.");
+    unused.severity = Severity::Advice;
+    unused.details.meta = Some(DiagnosticMetadata::Parse {
+        input: "a = 1\n".into(),
+        row: 1,
+        col: 1,
+        path: Some("config.toml".into()),
+    });
+
+    let sarif = SarifReport::new("lint")
+        .version("0.1.0")
+        .render(&[missing, unused]);
+    assert_eq!(
+        r#"{
+  "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+  "version": "2.1.0",
+  "runs": [
+    {
+      "tool": {
+        "driver": {
+          "name": "lint",
+          "version": "0.1.0",
+          "rules": [
+            {
+              "id": "lint::missing",
+              "help": {
+                "text": "Create it."
+              },
+              "helpUri": "https://example.com/missing"
+            },
+            {
+              "id": "lint::unused"
+            }
+          ]
+        }
+      },
+      "columnKind": "unicodeCodePoints",
+      "results": [
+        {
+          "ruleId": "lint::missing",
+          "ruleIndex": 0,
+          "level": "error",
+          "message": {
+            "text": "File is missing."
+          },
+          "locations": [
+            {
+              "physicalLocation": {
+                "artifactLocation": {
+                  "uri": "src/lib.rs"
+                }
+              }
+            }
+          ]
+        },
+        {
+          "ruleId": "lint::unused",
+          "ruleIndex": 1,
+          "level": "note",
+          "message": {
+            "text": "Unused key."
+          },
+          "locations": [
+            {
+              "physicalLocation": {
+                "artifactLocation": {
+                  "uri": "config.toml"
+                },
+                "region": {
+                  "startLine": 1,
+                  "startColumn": 1
+                }
+              }
+            }
+          ]
+        }
+      ]
+    }
+  ]
+}"#,
+        sarif
+    );
+}
+
+#[test]
+fn shared_rules() {
+    let errors = vec![
+        error("lint::a", "First."),
+        error("lint::b", "Second."),
+        error("lint::a", "Third."),
+    ];
+    let sarif = SarifReport::new("lint")
+        .artifact_uri("lint.toml")
+        .render(&errors);
+    assert_eq!(2, sarif.matches(r#""id": "lint::"#).count());
+    assert_eq!(3, sarif.matches(r#""ruleId": "lint::"#).count());
+    assert!(sarif.contains("\"ruleId\": \"lint::a\",\n          \"ruleIndex\": 0,\n          \"level\": \"error\",\n          \"message\": {\n            \"text\": \"Third.\""));
+}
+
+#[test]
+fn spans() {
+    let mut err = error("lint::duplicate", "Duplicate key.");
+    err.details.meta = Some(DiagnosticMetadata::ParseSpan {
+        input: "a = 1\na = 2\n".into(),
+        span: (6..7).into(),
+        path: Some("config.toml".into()),
+    });
+    err.details.labels = vec![
+        LabeledSpan::secondary(0..1, "first defined here"),
+        LabeledSpan::primary(6..7, "defined again here"),
+    ];
+    let sarif = SarifReport::new("lint").render(&[err]);
+    assert!(sarif.contains(
+        r#""region": {
+                  "startLine": 2,
+                  "startColumn": 1,
+                  "endLine": 2,
+                  "endColumn": 2,
+                  "byteOffset": 6,
+                  "byteLength": 1
+                }"#
+    ));
+    assert!(sarif.contains(r#""relatedLocations": ["#));
+    assert!(sarif.contains(r#""id": 0,"#));
+    assert!(sarif.contains(r#""text": "first defined here""#));
+    assert!(sarif.contains(r#""text": "defined again here""#));
+}
+
+#[test]
+fn no_location() {
+    let mut err = error("lint::net", "Timed out.");
+    err.details.meta = Some(DiagnosticMetadata::Net {
+        url: "https://example.com".into(),
+    });
+    let sarif = SarifReport::new("lint").render([&err]);
+    assert!(sarif.contains(r#""results": []"#));
+    assert!(!sarif.contains("lint::net"));
+
+    let sarif = SarifReport::new("lint")
+        .artifact_uri("lint.toml")
+        .render([&err]);
+    assert!(sarif.contains(
+        r#""locations": [
+            {
+              "physicalLocation": {
+                "artifactLocation": {
+                  "uri": "lint.toml"
+                }
+              }
+            }
+          ]"#
+    ));
+}
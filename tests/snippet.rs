@@ -0,0 +1,155 @@
+use thisdiagnostic::{
+    ColorChoice, DefaultReportHandler, DiagnosticError, DiagnosticMetadata, IntoDiagnostic,
+    SourceSpan, Theme,
+};
+
+fn render_meta(meta: DiagnosticMetadata) -> String {
+    thisdiagnostic::set_hook(Box::new(
+        DefaultReportHandler::new()
+            .color(ColorChoice::Never)
+            .theme(Theme::ascii())
+            .backtrace(false),
+    ));
+    let err: Result<(), DiagnosticError> = Err(std::fmt::Error).into_diagnostic("snippet::syntax");
+    let mut err = err.unwrap_err();
+    err.details.meta = Some(meta);
+    format!("{:?}", err)
+}
+
+fn render(input: &str, row: usize, col: usize) -> String {
+    render_meta(DiagnosticMetadata::Parse {
+        input: input.into(),
+        row,
+        col,
+        path: None,
+    })
+}
+
+fn render_span(input: &str, span: impl Into<SourceSpan>) -> String {
+    render_meta(DiagnosticMetadata::ParseSpan {
+        input: input.into(),
+        span: span.into(),
+        path: None,
+    })
+}
+
+#[test]
+fn context_and_caret() {
+    let input = "a = 1\nb = 2\nc = ?\nd = 4\ne = 5\nf = 6\n";
+    assert_eq!(
+        render(input, 3, 5),
+        "snippet::syntax - line: 3, col: 5\n\
+         \n  |\
+         \n1 | a = 1\
+         \n2 | b = 2\
+         \n3 | c = ?\
+         \n  |     ^\
+         \n4 | d = 4\
+         \n5 | e = 5\
+         \n  |\
+         \n\nan error occurred when formatting an argument"
+    );
+}
+
+#[test]
+fn gutter_width() {
+    let input = (1..=12)
+        .map(|n| format!("line {}", n))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let rendered = render(&input, 10, 1);
+    assert!(rendered.contains("\n 8 | line 8\n"));
+    assert!(rendered.contains("\n10 | line 10\n   | ^\n"));
+    assert!(rendered.contains("\n12 | line 12\n   |\n"));
+}
+
+#[test]
+fn tabs() {
+    let rendered = render("\tkey = ?", 1, 8);
+    assert!(rendered.contains("\n1 |     key = ?\n  |           ^\n"));
+}
+
+#[test]
+fn wide_characters() {
+    let rendered = render("名前 = 値?", 1, 6);
+    assert!(rendered.contains("\n1 | 名前 = 値?\n  |        ^^\n"));
+}
+
+#[test]
+fn crlf() {
+    let rendered = render("a = 1\r\nb = ?\r\n", 2, 5);
+    assert!(rendered.contains("\n1 | a = 1\n2 | b = ?\n  |     ^\n"));
+    assert!(!rendered.contains('\r'));
+}
+
+#[test]
+fn past_end_of_line() {
+    let rendered = render("a =", 1, 4);
+    assert!(rendered.contains("\n1 | a =\n  |    ^\n"));
+}
+
+#[test]
+fn out_of_range_row() {
+    let rendered = render("a = 1", 5, 1);
+    assert_eq!(
+        "snippet::syntax - line: 5, col: 1\n\nan error occurred when formatting an argument",
+        rendered
+    );
+}
+
+#[test]
+fn span_underlines_token() {
+    let input = "a = 1\nb = 2\nc = ???\nd = 4\n";
+    assert_eq!(
+        render_span(input, 16..19),
+        "snippet::syntax - line: 3, col: 5\n\
+         \n  |\
+         \n1 | a = 1\
+         \n2 | b = 2\
+         \n3 | c = ???\
+         \n  |     ^^^\
+         \n4 | d = 4\
+         \n  |\
+         \n\nan error occurred when formatting an argument"
+    );
+}
+
+#[test]
+fn span_wide_characters_and_tabs() {
+    let input = "\t名前 = 値";
+    let rendered = render_span(input, (1, "名前".len()));
+    assert!(rendered.contains("\n1 |     名前 = 値\n  |     ^^^^\n"));
+}
+
+#[test]
+fn empty_span() {
+    let rendered = render_span("a = 1", 2..2);
+    assert!(rendered.contains("\n1 | a = 1\n  |   ^\n"));
+}
+
+#[test]
+fn multiline_span() {
+    let rendered = render_span("a = [\n  1,\n]", 4..13);
+    assert!(rendered.contains("\n1 | a = [\n  |     ^\n2 |   1,\n"));
+}
+
+#[test]
+fn span_to_end_of_input() {
+    let rendered = render_span("a = 1\nb = 2", (4, usize::MAX));
+    assert!(rendered.starts_with("snippet::syntax - line: 1, col: 5\n"));
+    assert!(rendered.contains("\n1 | a = 1\n  |     ^\n2 | b = 2\n"));
+}
+
+#[test]
+fn span_after_trailing_newline() {
+    let rendered = render_span("a = 1\n\nb = 2\n", 13..13);
+    assert!(rendered.contains("\n2 |\n3 | b = 2\n4 |\n  | ^\n  |\n"));
+}
+
+#[test]
+fn span_out_of_range() {
+    assert_eq!(
+        "snippet::syntax - line: 1, col: 6\n\nan error occurred when formatting an argument",
+        render_span("a = 1", 10..12)
+    );
+}
@@ -0,0 +1,80 @@
+use thisdiagnostic::{
+    Color, ColorChoice, DefaultReportHandler, DiagnosticMetadata, IntoDiagnostic, LabeledSpan,
+    ReportHandler, Style, Theme,
+};
+
+fn render(theme: Theme, color: ColorChoice) -> String {
+    let err: Result<(), _> = Err(std::io::Error::other("Duplicate key."));
+    let mut err = err.into_diagnostic("theme::duplicate").unwrap_err();
+    err.help = Some("Remove one.".into());
+    err.details.meta = Some(DiagnosticMetadata::ParseSpan {
+        input: "{ a = 1, a = 2 }".into(),
+        span: (9..10).into(),
+        path: None,
+    });
+    err.details.labels = vec![
+        LabeledSpan::secondary(2..3, "first"),
+        LabeledSpan::primary(9..10, "second"),
+    ];
+    let handler = DefaultReportHandler::new()
+        .theme(theme)
+        .color(color)
+        .backtrace(false);
+    handler.render(&err)
+}
+
+#[test]
+fn unicode_glyphs() {
+    assert_eq!(
+        "theme::duplicate - line: 1, col: 10\n\
+         \n  │\
+         \n1 │ { a = 1, a = 2 }\
+         \n  │   ─      ━ second\
+         \n  │   │\
+         \n  │   first\
+         \n  │\
+         \n\nDuplicate key.\n\nhelp: Remove one.",
+        render(Theme::new(), ColorChoice::Never)
+    );
+}
+
+#[test]
+fn ascii_never_styles() {
+    assert_eq!(
+        "theme::duplicate - line: 1, col: 10\n\
+         \n  |\
+         \n1 | { a = 1, a = 2 }\
+         \n  |   -      ^ second\
+         \n  |   |\
+         \n  |   first\
+         \n  |\
+         \n\nDuplicate key.\n\nhelp: Remove one.",
+        render(Theme::ascii(), ColorChoice::Always)
+    );
+}
+
+#[test]
+fn monochrome() {
+    let rendered = render(Theme::monochrome(), ColorChoice::Always);
+    assert!(rendered.starts_with("\x1b[1mtheme::duplicate\x1b[0m"));
+    assert!(rendered.contains("\x1b[1mhelp\x1b[0m: Remove one."));
+    assert!(!rendered.contains("\x1b[3"));
+    assert!(!rendered.contains("\x1b[9"));
+}
+
+#[test]
+fn high_contrast() {
+    let rendered = render(Theme::high_contrast(), ColorChoice::Always);
+    assert!(rendered.starts_with("\x1b[1;91mtheme::duplicate\x1b[0m"));
+    assert!(rendered.contains("\x1b[1;93mhelp\x1b[0m: Remove one."));
+}
+
+#[test]
+fn custom_colors() {
+    let mut theme = Theme::ascii();
+    theme.styles.error = Style::fg(Color::Rgb(255, 128, 0)).bold();
+    theme.styles.help = Style::fg(Color::Magenta);
+    let rendered = render(theme, ColorChoice::Always);
+    assert!(rendered.starts_with("\x1b[1;38;2;255;128;0mtheme::duplicate\x1b[0m"));
+    assert!(rendered.contains("\x1b[35mhelp\x1b[0m: Remove one."));
+}
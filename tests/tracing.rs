@@ -0,0 +1,140 @@
+#![cfg(feature = "tracing")]
+
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use common::render;
+use thisdiagnostic::{Diagnostic, DiagnosticError, DiagnosticResult, IntoDiagnostic};
+use thiserror::Error;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_error::ErrorLayer;
+use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+
+/**
+An event's level and fields, rendered with `Debug`.
+*/
+struct Recorded {
+    level: Level,
+    fields: Vec<(String, String)>,
+}
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<Recorded>>>);
+
+impl<S: Subscriber> Layer<S> for Recorder {
+    fn on_event(&self, event: &Event<'_>, _: Context<'_, S>) {
+        struct Fields(Vec<(String, String)>);
+
+        impl Visit for Fields {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                self.0.push((field.name().into(), format!("{:?}", value)));
+            }
+        }
+
+        let mut fields = Fields(Vec::new());
+        event.record(&mut fields);
+        let level = *event.metadata().level();
+        self.0.lock().unwrap().push(Recorded {
+            level,
+            fields: fields.0,
+        });
+    }
+}
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Unexpected token.")]
+#[diagnostic(label = "tracing::syntax", help = "Remove it.", severity = "warning")]
+pub struct Syntax {
+    #[diagnostic(meta(input))]
+    input: String,
+    #[diagnostic(meta(row))]
+    row: usize,
+    #[diagnostic(meta(col))]
+    col: usize,
+    #[diagnostic(meta(path))]
+    path: std::path::PathBuf,
+}
+
+#[tracing::instrument]
+fn load_profile(name: &str) -> DiagnosticResult<()> {
+    read_config()
+}
+
+#[tracing::instrument]
+fn read_config() -> DiagnosticResult<()> {
+    Err(std::io::Error::other("permission denied")).into_diagnostic("tracing::read")
+}
+
+#[test]
+fn span_trace() {
+    let subscriber = tracing_subscriber::registry().with(ErrorLayer::default());
+    let err = tracing::subscriber::with_default(subscriber, || load_profile("dev").unwrap_err());
+    assert!(err.details.span_trace.is_some());
+    let rendered = render(&err);
+    let (message, spans) = rendered.split_once("\n\nIn spans:\n").unwrap();
+    assert_eq!("tracing::read\n\npermission denied", message);
+    assert!(
+        spans.starts_with(
+            "    0: tracing::read_config\
+         \n           at tests/tracing.rs:"
+        ),
+        "{}",
+        spans
+    );
+    assert!(spans.contains(
+        "\n    1: tracing::load_profile\
+         \n           with name=\"dev\"\
+         \n           at tests/tracing.rs:"
+    ));
+}
+
+#[test]
+fn no_error_layer() {
+    let err = load_profile("dev").unwrap_err();
+    assert!(err.details.span_trace.is_none());
+    assert_eq!("tracing::read\n\npermission denied", render(&err));
+}
+
+#[test]
+fn emit() {
+    let recorder = Recorder::default();
+    let subscriber = tracing_subscriber::registry().with(recorder.clone());
+    tracing::subscriber::with_default(subscriber, || {
+        let err: DiagnosticError = Syntax {
+            input: "a = 1\nb = ?\n".into(),
+            row: 2,
+            col: 5,
+            path: "config.toml".into(),
+        }
+        .into();
+        err.emit();
+        read_config().unwrap_err().emit();
+    });
+
+    let events = recorder.0.lock().unwrap();
+    let field = |idx: usize, name: &str| {
+        events[idx]
+            .fields
+            .iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value.clone())
+    };
+    assert_eq!(2, events.len());
+
+    assert_eq!(Level::WARN, events[0].level);
+    assert_eq!(Some("Unexpected token.".into()), field(0, "message"));
+    assert_eq!(Some("tracing::syntax".into()), field(0, "label"));
+    assert_eq!(Some("warning".into()), field(0, "severity"));
+    assert_eq!(Some("\"Remove it.\"".into()), field(0, "help"));
+    assert_eq!(Some("config.toml".into()), field(0, "meta.path"));
+    assert_eq!(Some("2".into()), field(0, "meta.row"));
+    assert_eq!(Some("5".into()), field(0, "meta.col"));
+    assert_eq!(None, field(0, "url"));
+
+    assert_eq!(Level::ERROR, events[1].level);
+    assert_eq!(Some("permission denied".into()), field(1, "message"));
+    assert_eq!(None, field(1, "help"));
+    assert_eq!(None, field(1, "meta.path"));
+}
@@ -0,0 +1,11 @@
+#![deny(deprecated)]
+
+use thisdiagnostic::Diagnostic;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("oops")]
+#[label("oops::bare")]
+pub struct Oops;
+
+fn main() {}
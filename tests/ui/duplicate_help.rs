@@ -0,0 +1,11 @@
+use thisdiagnostic::Diagnostic;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("oops")]
+#[label("oops::twice")]
+#[help("First.")]
+#[help("Second.")]
+pub struct Oops;
+
+fn main() {}
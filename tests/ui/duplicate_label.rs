@@ -0,0 +1,12 @@
+use thisdiagnostic::Diagnostic;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("oops")]
+pub enum Oops {
+    #[label("oops::first")]
+    #[label("oops::second")]
+    Twice,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use thisdiagnostic::Diagnostic;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("oops")]
+#[label("oops::bare")]
+#[diagnostic(label = "oops::unified")]
+pub struct Oops;
+
+fn main() {}
@@ -0,0 +1,9 @@
+use thisdiagnostic::Diagnostic;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("oops")]
+#[label(42)]
+pub struct Oops;
+
+fn main() {}
@@ -0,0 +1,14 @@
+use thisdiagnostic::Diagnostic;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("oops")]
+#[label("oops::parse")]
+pub struct Oops {
+    #[meta(input)]
+    input: String,
+    #[meta(row)]
+    row: usize,
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+use thisdiagnostic::{Diagnostic, SourceSpan};
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("oops")]
+#[diagnostic(label = "oops::parse")]
+pub struct Oops {
+    #[diagnostic(meta(span))]
+    span: SourceSpan,
+    #[diagnostic(meta(row))]
+    row: usize,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use thisdiagnostic::Diagnostic;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("oops")]
+#[label("oops::fs")]
+pub struct Oops {
+    #[meta(file)]
+    path: String,
+}
+
+fn main() {}
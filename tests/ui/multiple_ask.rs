@@ -0,0 +1,15 @@
+use thisdiagnostic::Diagnostic;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("inner")]
+#[diagnostic(label = "oops::inner")]
+pub struct Inner;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("oops")]
+pub enum Oops {
+    Both(#[ask] Inner, #[ask] Inner),
+}
+
+fn main() {}
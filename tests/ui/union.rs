@@ -0,0 +1,9 @@
+use thisdiagnostic::Diagnostic;
+
+#[derive(Diagnostic)]
+pub union Oops {
+    a: u32,
+    b: f32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use thisdiagnostic::Diagnostic;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("oops")]
+#[diagnostic(help_from_comments)]
+pub struct Oops;
+
+fn main() {}
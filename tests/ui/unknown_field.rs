@@ -0,0 +1,12 @@
+use thisdiagnostic::Diagnostic;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("oops")]
+pub enum Oops {
+    #[label("oops::missing")]
+    #[help("did you mean `{suggestion}`?")]
+    Missing { command: String },
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use thisdiagnostic::Diagnostic;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("oops")]
+#[label("oops::tuple")]
+#[help("no such thing as {1}")]
+pub struct Oops(String);
+
+fn main() {}
@@ -0,0 +1,9 @@
+use thisdiagnostic::Diagnostic;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("oops")]
+#[diagnostic(label = "oops::fatal", severity = "fatal")]
+pub struct Oops;
+
+fn main() {}
@@ -0,0 +1,11 @@
+use thisdiagnostic::Diagnostic;
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("oops")]
+pub enum Oops {
+    #[label(prefix = "oops")]
+    Prefixed,
+}
+
+fn main() {}
@@ -0,0 +1,72 @@
+mod common;
+
+use common::render;
+use thisdiagnostic::{
+    Diagnostic, DiagnosticResult, IntoDiagnostic, JsonReportHandler, ReportHandler, WrapDiagnostic,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error, Diagnostic)]
+#[error("Profile not found.")]
+#[diagnostic(label = "wrap::not_found", help = "Check your profiles.")]
+pub struct NotFound;
+
+fn read_config() -> DiagnosticResult<String> {
+    let err: Result<String, _> = Err(std::io::Error::other("permission denied"));
+    err.into_diagnostic("wrap::read")
+        .wrap_err("while reading config.toml")
+}
+
+fn load_profile(name: &str) -> DiagnosticResult<String> {
+    read_config().wrap_err_with(|| format!("while loading profile {}", name))
+}
+
+#[test]
+fn diagnostic_result() {
+    let err = load_profile("dev").unwrap_err();
+    assert_eq!(
+        vec!["while reading config.toml", "while loading profile dev"],
+        err.details.context
+    );
+    assert_eq!(
+        "wrap::read\n\n\
+         while loading profile dev\n\n\
+         Caused by:\n    \
+         0: while reading config.toml\n    \
+         1: permission denied",
+        render(&err)
+    );
+    assert!(err.error.downcast_ref::<std::io::Error>().is_some());
+}
+
+#[test]
+fn diagnostic_errors() {
+    let err: Result<(), NotFound> = Err(NotFound);
+    let err = err.wrap_err("while loading profile dev").unwrap_err();
+    assert_eq!(
+        "wrap::not_found\n\n\
+         while loading profile dev\n\n\
+         Caused by:\n    \
+         0: Profile not found.\n\n\
+         help: Check your profiles.",
+        render(&err)
+    );
+    assert!(err.error.downcast_ref::<NotFound>().is_some());
+}
+
+#[test]
+fn lazy() {
+    let ok: Result<(), NotFound> = Ok(());
+    ok.wrap_err_with(|| -> String { panic!("only called on errors") })
+        .unwrap();
+}
+
+#[test]
+fn json() {
+    let err = load_profile("dev").unwrap_err();
+    let json = JsonReportHandler::new().line_delimited(true).render(&err);
+    assert!(json.contains(r#""message":"permission denied","#));
+    assert!(json.contains(
+        r#""context":["while loading profile dev","while reading config.toml"],"causes":[]"#
+    ));
+}
@@ -0,0 +1,239 @@
+use proc_macro2::Span;
+use syn::{
+    Data, DataEnum, DataStruct, DeriveInput, Error, Fields, Generics, Ident, Index, Member, Result,
+    Type,
+};
+
+use crate::attr::{self, Attrs};
+
+pub enum Input<'a> {
+    Struct(Struct<'a>),
+    Enum(Enum<'a>),
+}
+
+pub struct Struct<'a> {
+    pub ident: Ident,
+    pub generics: &'a Generics,
+    pub attrs: Attrs,
+    pub fields: Vec<Field<'a>>,
+}
+
+pub struct Enum<'a> {
+    pub ident: Ident,
+    pub generics: &'a Generics,
+    pub attrs: Attrs,
+    pub variants: Vec<Variant<'a>>,
+}
+
+pub struct Variant<'a> {
+    pub ident: Ident,
+    pub attrs: Attrs,
+    pub fields: Vec<Field<'a>>,
+}
+
+pub struct Field<'a> {
+    pub attrs: Attrs,
+    pub member: Member,
+    pub ty: &'a Type,
+    /**
+    Whether thiserror treats this field as the error's source: it's marked
+    `#[source]` or `#[from]`, or it's named `source`.
+    */
+    pub source: bool,
+}
+
+impl<'a> Input<'a> {
+    pub fn from_syn(node: &'a DeriveInput) -> Result<Self> {
+        match &node.data {
+            Data::Struct(data) => Struct::from_syn(node, data).map(Input::Struct),
+            Data::Enum(data) => Enum::from_syn(node, data).map(Input::Enum),
+            Data::Union(data) => Err(Error::new_spanned(
+                data.union_token,
+                "#[derive(Diagnostic)] does not support unions",
+            )),
+        }
+    }
+}
+
+impl<'a> Struct<'a> {
+    fn from_syn(node: &'a DeriveInput, data: &'a DataStruct) -> Result<Self> {
+        let attrs = attr::get(&node.attrs)?;
+        if let Some(ask) = &attrs.ask {
+            return Err(Error::new_spanned(ask, "`ask` is only allowed on fields"));
+        }
+        if let Some(meta) = &attrs.meta {
+            return Err(Error::new_spanned(
+                &meta.original,
+                "`meta` is only allowed on fields",
+            ));
+        }
+        let fields = Field::multiple_from_syn(&data.fields)?;
+        check_single_ask(&fields, "only one field may be marked `ask`")?;
+        Ok(Struct {
+            ident: node.ident.clone(),
+            generics: &node.generics,
+            attrs,
+            fields,
+        })
+    }
+
+    pub fn ask_field(&self) -> Option<&Field<'a>> {
+        self.fields.iter().find(|field| field.attrs.ask.is_some())
+    }
+
+    pub fn source_field(&self) -> Option<&Field<'a>> {
+        source_field(&self.fields)
+    }
+}
+
+impl<'a> Enum<'a> {
+    fn from_syn(node: &'a DeriveInput, data: &'a DataEnum) -> Result<Self> {
+        let attrs = attr::get(&node.attrs)?;
+        if let Some(ask) = &attrs.ask {
+            return Err(Error::new_spanned(ask, "`ask` is only allowed on fields"));
+        }
+        if let Some(meta) = &attrs.meta {
+            return Err(Error::new_spanned(
+                &meta.original,
+                "`meta` is only allowed on fields",
+            ));
+        }
+        if let Some(label) = &attrs.label {
+            return Err(Error::new_spanned(
+                label,
+                "`label` on an enum only takes a prefix, put the label on each variant instead",
+            ));
+        }
+        if let Some(help) = &attrs.help {
+            return Err(Error::new_spanned(
+                help,
+                "`help` is not allowed on enums, put it on each variant instead",
+            ));
+        }
+        let variants = data
+            .variants
+            .iter()
+            .map(Variant::from_syn)
+            .collect::<Result<_>>()?;
+        Ok(Enum {
+            ident: node.ident.clone(),
+            generics: &node.generics,
+            attrs,
+            variants,
+        })
+    }
+}
+
+impl<'a> Variant<'a> {
+    fn from_syn(node: &'a syn::Variant) -> Result<Self> {
+        let attrs = attr::get(&node.attrs)?;
+        if let Some(help_from_docs) = &attrs.help_from_docs {
+            return Err(Error::new_spanned(
+                help_from_docs,
+                "`help_from_docs` is only allowed on the enum itself",
+            ));
+        }
+        if let Some(prefix) = &attrs.label_prefix {
+            return Err(Error::new_spanned(
+                prefix,
+                "`label(prefix = \"...\")` is only allowed on the enum itself",
+            ));
+        }
+        if let Some(ask) = &attrs.ask {
+            return Err(Error::new_spanned(ask, "`ask` is only allowed on fields"));
+        }
+        if let Some(meta) = &attrs.meta {
+            return Err(Error::new_spanned(
+                &meta.original,
+                "`meta` is only allowed on fields",
+            ));
+        }
+        let fields = Field::multiple_from_syn(&node.fields)?;
+        check_single_ask(&fields, "only one field per variant may be marked `ask`")?;
+        Ok(Variant {
+            ident: node.ident.clone(),
+            attrs,
+            fields,
+        })
+    }
+
+    pub fn ask_field(&self) -> Option<&Field<'a>> {
+        self.fields.iter().find(|field| field.attrs.ask.is_some())
+    }
+
+    pub fn source_field(&self) -> Option<&Field<'a>> {
+        source_field(&self.fields)
+    }
+}
+
+impl<'a> Field<'a> {
+    fn multiple_from_syn(fields: &'a Fields) -> Result<Vec<Self>> {
+        fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| Field::from_syn(i, field))
+            .collect()
+    }
+
+    fn from_syn(i: usize, node: &'a syn::Field) -> Result<Self> {
+        let attrs = attr::get(&node.attrs)?;
+        if let Some(label) = attrs.label.as_ref().or(attrs.label_prefix.as_ref()) {
+            return Err(Error::new_spanned(
+                label,
+                "`label` is not allowed on fields",
+            ));
+        }
+        if let Some(help) = &attrs.help {
+            return Err(Error::new_spanned(help, "`help` is not allowed on fields"));
+        }
+        if let Some(help_from_docs) = &attrs.help_from_docs {
+            return Err(Error::new_spanned(
+                help_from_docs,
+                "`help_from_docs` is not allowed on fields",
+            ));
+        }
+        if let Some(severity) = &attrs.severity {
+            return Err(Error::new_spanned(
+                severity,
+                "`severity` is not allowed on fields",
+            ));
+        }
+        if let Some(url) = &attrs.url {
+            return Err(Error::new_spanned(url, "`url` is not allowed on fields"));
+        }
+        Ok(Field {
+            attrs,
+            member: node.ident.clone().map(Member::Named).unwrap_or_else(|| {
+                Member::Unnamed(Index {
+                    index: i as u32,
+                    span: Span::call_site(),
+                })
+            }),
+            ty: &node.ty,
+            source: node
+                .attrs
+                .iter()
+                .any(|attr| attr.path.is_ident("source") || attr.path.is_ident("from"))
+                || node.ident.as_ref().is_some_and(|ident| ident == "source"),
+        })
+    }
+}
+
+/**
+The field whose `Diagnostic` is this one's
+cause: the `ask` field, or failing that the error's source.
+*/
+fn source_field<'a, 'b>(fields: &'b [Field<'a>]) -> Option<&'b Field<'a>> {
+    fields
+        .iter()
+        .find(|field| field.attrs.ask.is_some())
+        .or_else(|| fields.iter().find(|field| field.source))
+}
+
+fn check_single_ask(fields: &[Field], message: &str) -> Result<()> {
+    let mut asks = fields.iter().filter_map(|field| field.attrs.ask.as_ref());
+    if let (Some(_), Some(second)) = (asks.next(), asks.next()) {
+        return Err(Error::new_spanned(second, message));
+    }
+    Ok(())
+}
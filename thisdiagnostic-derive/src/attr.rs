@@ -0,0 +1,273 @@
+use quote::ToTokens;
+use syn::spanned::Spanned;
+use syn::{Attribute, Error, Ident, Lit, LitStr, Meta, NestedMeta, Path, Result};
+
+/**
+Diagnostic attributes found on a container, variant, or field.
+*/
+#[derive(Default)]
+pub struct Attrs {
+    pub label: Option<LitStr>,
+    pub label_prefix: Option<LitStr>,
+    pub help: Option<LitStr>,
+    pub ask: Option<Path>,
+    pub meta: Option<MetaAttr>,
+    pub help_from_docs: Option<Path>,
+    pub severity: Option<Ident>,
+    pub url: Option<LitStr>,
+    pub docs: Option<String>,
+    pub deprecated: Vec<Path>,
+}
+
+/**
+Which part of `DiagnosticMetadata` a `meta(...)` field provides.
+*/
+#[derive(Clone, Copy, PartialEq)]
+pub enum MetaKind {
+    Path,
+    Url,
+    Input,
+    Row,
+    Col,
+    Span,
+}
+
+pub struct MetaAttr {
+    pub kind: MetaKind,
+    pub original: Meta,
+}
+
+/**
+Keys that are still accepted as attributes of their own, e.g.
+`#[label("...")]`, instead of inside `#[diagnostic(...)]`.
+*/
+const BARE: &[&str] = &["label", "help", "ask", "meta", "severity", "url"];
+
+pub fn get(input: &[Attribute]) -> Result<Attrs> {
+    let mut attrs = Attrs::default();
+    let mut docs = Vec::new();
+
+    for attr in input {
+        if attr.path.is_ident("doc") {
+            if let Ok(Meta::NameValue(pair)) = attr.parse_meta() {
+                if let Lit::Str(doc) = pair.lit {
+                    docs.push(doc.value());
+                }
+            }
+        } else if attr.path.is_ident("diagnostic") {
+            let list = match attr.parse_meta()? {
+                Meta::List(list) => list,
+                meta => return Err(Error::new_spanned(meta, "expected #[diagnostic(...)]")),
+            };
+            for nested in &list.nested {
+                match nested {
+                    NestedMeta::Meta(meta) => parse_key(meta, &mut attrs)?,
+                    NestedMeta::Lit(lit) => {
+                        return Err(Error::new_spanned(lit, "expected a key, found a literal"));
+                    }
+                }
+            }
+        } else if BARE.iter().any(|name| attr.path.is_ident(name)) {
+            parse_key(&attr.parse_meta()?, &mut attrs)?;
+            attrs.deprecated.push(attr.path.clone());
+        }
+    }
+
+    attrs.docs = normalize_docs(&docs);
+    Ok(attrs)
+}
+
+/**
+Parses a single key, given either as a bare attribute (`#[help("...")]`) or
+inside `#[diagnostic(...)]` (`help = "..."` or `help("...")`).
+*/
+fn parse_key(meta: &Meta, attrs: &mut Attrs) -> Result<()> {
+    let path = meta.path();
+    let name = path_to_string(path);
+    match name.as_str() {
+        "label" => match parse_label_prefix(meta)? {
+            Some(prefix) => set_once(&mut attrs.label_prefix, prefix, meta, "label(prefix)"),
+            None => {
+                let label = parse_string(meta, &name)?;
+                set_once(&mut attrs.label, label, meta, &name)
+            }
+        },
+        "help" => {
+            let help = parse_string(meta, &name)?;
+            set_once(&mut attrs.help, help, meta, &name)
+        }
+        "ask" => {
+            let ask = parse_flag(meta, &name)?;
+            set_once(&mut attrs.ask, ask, meta, &name)
+        }
+        "severity" => {
+            let severity = parse_string(meta, &name)?;
+            let variant = match severity.value().as_str() {
+                "error" => "Error",
+                "warning" => "Warning",
+                "advice" => "Advice",
+                _ => {
+                    return Err(Error::new_spanned(
+                        severity,
+                        "expected one of \"error\", \"warning\" or \"advice\"",
+                    ))
+                }
+            };
+            let severity = Ident::new(variant, severity.span());
+            set_once(&mut attrs.severity, severity, meta, &name)
+        }
+        "url" => {
+            let url = parse_string(meta, &name)?;
+            set_once(&mut attrs.url, url, meta, &name)
+        }
+        "help_from_docs" => {
+            let help_from_docs = parse_flag(meta, &name)?;
+            set_once(&mut attrs.help_from_docs, help_from_docs, meta, &name)
+        }
+        "meta" => {
+            let kind = parse_meta_kind(meta)?;
+            let original = meta.clone();
+            set_once(&mut attrs.meta, MetaAttr { kind, original }, meta, &name)
+        }
+        _ => Err(Error::new_spanned(
+            path,
+            format!("unknown key `{}` in #[diagnostic]", name),
+        )),
+    }
+}
+
+/**
+Joins `///` lines into paragraphs, collapsing runs of whitespace. Paragraphs
+are separated by a blank line.
+*/
+fn normalize_docs(lines: &[String]) -> Option<String> {
+    let mut paragraphs = Vec::new();
+    let mut current = Vec::new();
+    for line in lines.iter().flat_map(|line| line.split('\n')) {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        if words.is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(current.join(" "));
+                current.clear();
+            }
+        } else {
+            current.push(words.join(" "));
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current.join(" "));
+    }
+
+    if paragraphs.is_empty() {
+        None
+    } else {
+        Some(paragraphs.join("\n\n"))
+    }
+}
+
+pub fn path_to_string(path: &Path) -> String {
+    path.segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+fn set_once<T>(slot: &mut Option<T>, value: T, tokens: &dyn ToTokens, name: &str) -> Result<()> {
+    if slot.is_some() {
+        return Err(Error::new_spanned(
+            tokens.to_token_stream(),
+            format!("duplicate `{}`", name),
+        ));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+fn parse_flag(meta: &Meta, name: &str) -> Result<Path> {
+    match meta {
+        Meta::Path(path) => Ok(path.clone()),
+        meta => Err(Error::new_spanned(
+            meta,
+            format!("`{}` does not take any arguments", name),
+        )),
+    }
+}
+
+fn parse_string(meta: &Meta, name: &str) -> Result<LitStr> {
+    let expected = || format!("expected `{} = \"...\"`", name);
+
+    let list = match meta {
+        Meta::NameValue(pair) => {
+            return match &pair.lit {
+                Lit::Str(value) => Ok(value.clone()),
+                lit => Err(Error::new_spanned(lit, "expected a string literal")),
+            };
+        }
+        Meta::List(list) => list,
+        Meta::Path(path) => return Err(Error::new_spanned(path, expected())),
+    };
+
+    let mut nested = list.nested.iter();
+    let value = match (nested.next(), nested.next()) {
+        (Some(NestedMeta::Lit(Lit::Str(value))), None) => value.clone(),
+        (Some(NestedMeta::Lit(lit)), None) => {
+            return Err(Error::new_spanned(lit, "expected a string literal"));
+        }
+        (Some(NestedMeta::Meta(meta)), None) => {
+            let key = meta.path();
+            return Err(Error::new_spanned(
+                key,
+                format!("unknown key `{}` in `{}`", path_to_string(key), name),
+            ));
+        }
+        (Some(_), Some(extra)) => {
+            return Err(Error::new(
+                extra.span(),
+                format!("`{}` takes a single string literal", name),
+            ));
+        }
+        (None, _) => return Err(Error::new_spanned(list, expected())),
+    };
+
+    Ok(value)
+}
+
+fn parse_label_prefix(meta: &Meta) -> Result<Option<LitStr>> {
+    let list = match meta {
+        Meta::List(list) if list.nested.len() == 1 => list,
+        _ => return Ok(None),
+    };
+
+    match &list.nested[0] {
+        NestedMeta::Meta(Meta::NameValue(pair)) if pair.path.is_ident("prefix") => {
+            match &pair.lit {
+                Lit::Str(prefix) => Ok(Some(prefix.clone())),
+                lit => Err(Error::new_spanned(lit, "expected a string literal")),
+            }
+        }
+        _ => Ok(None),
+    }
+}
+
+fn parse_meta_kind(meta: &Meta) -> Result<MetaKind> {
+    const EXPECTED: &str =
+        "expected one of `meta(path)`, `meta(url)`, `meta(input)`, `meta(row)`, `meta(col)` or `meta(span)`";
+
+    let list = match meta {
+        Meta::List(list) if list.nested.len() == 1 => list,
+        meta => return Err(Error::new_spanned(meta, EXPECTED)),
+    };
+
+    let kind = match &list.nested[0] {
+        NestedMeta::Meta(Meta::Path(path)) if path.is_ident("path") => MetaKind::Path,
+        NestedMeta::Meta(Meta::Path(path)) if path.is_ident("url") => MetaKind::Url,
+        NestedMeta::Meta(Meta::Path(path)) if path.is_ident("input") => MetaKind::Input,
+        NestedMeta::Meta(Meta::Path(path)) if path.is_ident("row") => MetaKind::Row,
+        NestedMeta::Meta(Meta::Path(path)) if path.is_ident("col") => MetaKind::Col,
+        NestedMeta::Meta(Meta::Path(path)) if path.is_ident("span") => MetaKind::Span,
+        other => return Err(Error::new_spanned(other, EXPECTED)),
+    };
+
+    Ok(kind)
+}
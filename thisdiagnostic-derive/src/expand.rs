@@ -0,0 +1,625 @@
+use proc_macro2::TokenStream;
+use proc_macro2::TokenTree;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{parse_quote, DeriveInput, GenericParam, Generics, LitStr, Result, Type, WherePredicate};
+
+use crate::ast::{Enum, Field, Input, Struct};
+use crate::attr::{self, Attrs};
+use crate::fmt::Format;
+use crate::meta::FieldMeta;
+
+pub fn derive(node: &DeriveInput) -> Result<TokenStream> {
+    let input = Input::from_syn(node)?;
+    let warnings = deprecation_warnings(&input);
+    let expanded = match input {
+        Input::Struct(input) => impl_struct(input)?,
+        Input::Enum(input) => impl_enum(input)?,
+    };
+    Ok(quote! {
+        #expanded
+        #warnings
+    })
+}
+
+/**
+Warns about every bare `#[label]`, `#[help]`, `#[ask]`, `#[meta]`,
+`#[severity]` or `#[url]`, pointing at the attribute itself.
+
+Stable proc macros cannot emit warnings directly, so this refers to a
+`#[deprecated]` item through an identifier carrying the attribute's span.
+*/
+fn deprecation_warnings(input: &Input) -> Option<TokenStream> {
+    let (attrs, fields): (Vec<&Attrs>, Vec<&Field>) = match input {
+        Input::Struct(input) => (vec![&input.attrs], input.fields.iter().collect()),
+        Input::Enum(input) => (
+            std::iter::once(&input.attrs)
+                .chain(input.variants.iter().map(|variant| &variant.attrs))
+                .collect(),
+            input
+                .variants
+                .iter()
+                .flat_map(|variant| &variant.fields)
+                .collect(),
+        ),
+    };
+
+    let warnings = attrs
+        .into_iter()
+        .chain(fields.into_iter().map(|field| &field.attrs))
+        .flat_map(|attrs| &attrs.deprecated)
+        .map(|path| {
+            let name = attr::path_to_string(path);
+            let note = match name.as_str() {
+                "ask" => "use #[diagnostic(ask)] instead of #[ask]".to_string(),
+                "meta" => "use #[diagnostic(meta(...))] instead of #[meta(...)]".to_string(),
+                _ => format!(
+                    "use #[diagnostic({} = \"...\")] instead of #[{}(\"...\")]",
+                    name, name
+                ),
+            };
+            let ident = format_ident!("{}", name, span = path.span());
+            quote! {
+                {
+                    #[deprecated(note = #note)]
+                    #[allow(non_upper_case_globals)]
+                    const #ident: () = ();
+                    #ident
+                };
+            }
+        })
+        .collect::<Vec<_>>();
+
+    (!warnings.is_empty()).then(|| {
+        quote! {
+            const _: () = {
+                #(#warnings)*
+            };
+        }
+    })
+}
+
+fn impl_struct(input: Struct) -> Result<TokenStream> {
+    let name = &input.ident;
+    let ask = input.ask_field();
+    let generics = with_bounds(input.generics, ask.map(|field| field.ty));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let prefix = input.attrs.label_prefix.as_ref();
+
+    let label = match &input.attrs.label {
+        Some(label) => {
+            let label = Format::parse(&prefixed(label, prefix), &input.fields)?;
+            let bindings = label.bindings();
+            let label = label.expand();
+            let bindings = (!bindings.is_empty()).then(|| {
+                quote! {
+                    let #name { #bindings .. } = self;
+                }
+            });
+            quote! {
+                #bindings
+                #label
+            }
+        }
+        None => match ask {
+            Some(ask) => {
+                let member = &ask.member;
+                quote! { self.#member.label() }
+            }
+            None => default_label(prefix, &name.to_string()),
+        },
+    };
+
+    let help = match &input.attrs.help {
+        Some(help) => {
+            let help = Format::parse(help, &input.fields)?;
+            let bindings = help.bindings();
+            let help = help.expand();
+            let bindings = (!bindings.is_empty()).then(|| {
+                quote! {
+                    let #name { #bindings .. } = self;
+                }
+            });
+            quote! {
+                #bindings
+                Some(#help)
+            }
+        }
+        None => match ask {
+            Some(ask) => {
+                let member = &ask.member;
+                quote! { self.#member.help() }
+            }
+            None => docs_help(&input.attrs, &input.attrs),
+        },
+    };
+
+    let meta = match (ask, FieldMeta::from_fields(&input.fields)?) {
+        (Some(ask), _) => {
+            let member = &ask.member;
+            Some(quote! { self.#member.meta() })
+        }
+        (None, Some(meta)) => {
+            let bindings = meta.bindings();
+            let meta = meta.expand();
+            Some(quote! {
+                let #name { #bindings .. } = self;
+                Some(#meta)
+            })
+        }
+        (None, None) => None,
+    };
+    let meta = meta.map(|meta| {
+        quote! {
+            fn meta(&self) -> Option<::thisdiagnostic::DiagnosticMetadata> {
+                #meta
+            }
+        }
+    });
+
+    let severity = match (&input.attrs.severity, ask) {
+        (Some(severity), _) => Some(quote! { ::thisdiagnostic::Severity::#severity }),
+        (None, Some(ask)) => {
+            let member = &ask.member;
+            Some(quote! { self.#member.severity() })
+        }
+        (None, None) => None,
+    };
+    let severity = severity.map(|severity| {
+        quote! {
+            fn severity(&self) -> ::thisdiagnostic::Severity {
+                #severity
+            }
+        }
+    });
+
+    let url = match (&input.attrs.url, ask) {
+        (Some(url), _) => {
+            let url = Format::parse_url(url, &input.fields)?;
+            let bindings = url.bindings();
+            let url = url.expand();
+            let bindings = (!bindings.is_empty()).then(|| {
+                quote! {
+                    let #name { #bindings .. } = self;
+                }
+            });
+            Some(quote! {
+                #bindings
+                Some(#url)
+            })
+        }
+        (None, Some(ask)) => {
+            let member = &ask.member;
+            Some(quote! { self.#member.url() })
+        }
+        (None, None) => None,
+    };
+    let url = url.map(|url| {
+        quote! {
+            fn url(&self) -> Option<String> {
+                #url
+            }
+        }
+    });
+
+    let labels = ask.map(|ask| {
+        let member = &ask.member;
+        quote! {
+            fn labels(&self) -> Vec<::thisdiagnostic::LabeledSpan> {
+                self.#member.labels()
+            }
+        }
+    });
+
+    let diagnostic_source = input.source_field().map(|source| {
+        let member = &source.member;
+        let source = as_diagnostic_source(quote! { &self.#member });
+        quote! {
+            fn diagnostic_source(&self) -> Option<&dyn ::thisdiagnostic::Diagnostic> {
+                #source
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics Diagnostic for #name #ty_generics #where_clause {
+            fn label(&self) -> String {
+                #label
+            }
+
+            fn help(&self) -> Option<String> {
+                #help
+            }
+
+            #meta
+            #severity
+            #url
+            #labels
+            #diagnostic_source
+        }
+    })
+}
+
+fn impl_enum(input: Enum) -> Result<TokenStream> {
+    let name = &input.ident;
+    let asks = input
+        .variants
+        .iter()
+        .filter_map(|variant| variant.ask_field())
+        .map(|field| field.ty)
+        .collect::<Vec<_>>();
+    let generics = with_bounds(input.generics, asks.iter().copied());
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    if input.variants.is_empty() {
+        return Ok(quote! {
+            impl #impl_generics Diagnostic for #name #ty_generics #where_clause {
+                fn label(&self) -> String {
+                    match *self {}
+                }
+
+                fn help(&self) -> Option<String> {
+                    match *self {}
+                }
+            }
+        });
+    }
+
+    let prefix = input.attrs.label_prefix.as_ref();
+    let label_arms = input
+        .variants
+        .iter()
+        .map(|variant| {
+            let id = &variant.ident;
+            Ok(if let Some(label) = &variant.attrs.label {
+                let label = Format::parse(&prefixed(label, prefix), &variant.fields)?;
+                let bindings = label.bindings();
+                let label = label.expand();
+                quote! {
+                    #name::#id { #bindings .. } => #label,
+                }
+            } else if let Some(ask) = variant.ask_field() {
+                let member = &ask.member;
+                quote! {
+                    #name::#id { #member: err, .. } => err.label(),
+                }
+            } else {
+                let label = default_label(prefix, &format!("{}::{}", name, id));
+                quote! {
+                    #name::#id { .. } => #label,
+                }
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let help_arms = input
+        .variants
+        .iter()
+        .map(|variant| {
+            let id = &variant.ident;
+            Ok(if let Some(help) = &variant.attrs.help {
+                let help = Format::parse(help, &variant.fields)?;
+                let bindings = help.bindings();
+                let help = help.expand();
+                quote! {
+                    #name::#id { #bindings .. } => Some(#help),
+                }
+            } else if let Some(ask) = variant.ask_field() {
+                let member = &ask.member;
+                quote! {
+                    #name::#id { #member: err, .. } => err.help(),
+                }
+            } else {
+                let help = docs_help(&input.attrs, &variant.attrs);
+                quote! {
+                    #name::#id { .. } => #help,
+                }
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut has_meta = false;
+    let meta_arms = input
+        .variants
+        .iter()
+        .map(|variant| {
+            let id = &variant.ident;
+            let field_meta = FieldMeta::from_fields(&variant.fields)?;
+            Ok(match (variant.ask_field(), field_meta) {
+                (Some(ask), _) => {
+                    has_meta = true;
+                    let member = &ask.member;
+                    quote! {
+                        #name::#id { #member: err, .. } => err.meta(),
+                    }
+                }
+                (None, Some(meta)) => {
+                    has_meta = true;
+                    let bindings = meta.bindings();
+                    let meta = meta.expand();
+                    quote! {
+                        #name::#id { #bindings .. } => Some(#meta),
+                    }
+                }
+                (None, None) => quote! {
+                    #name::#id { .. } => None,
+                },
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let meta = has_meta.then(|| {
+        quote! {
+            fn meta(&self) -> Option<::thisdiagnostic::DiagnosticMetadata> {
+                match self {
+                    #(#meta_arms)*
+                }
+            }
+        }
+    });
+
+    let mut has_severity = input.attrs.severity.is_some();
+    let severity_arms = input
+        .variants
+        .iter()
+        .map(|variant| {
+            let id = &variant.ident;
+            match (
+                &variant.attrs.severity,
+                variant.ask_field(),
+                &input.attrs.severity,
+            ) {
+                (Some(severity), _, _) => {
+                    has_severity = true;
+                    quote! {
+                        #name::#id { .. } => ::thisdiagnostic::Severity::#severity,
+                    }
+                }
+                (None, Some(ask), _) => {
+                    has_severity = true;
+                    let member = &ask.member;
+                    quote! {
+                        #name::#id { #member: err, .. } => err.severity(),
+                    }
+                }
+                (None, None, Some(severity)) => quote! {
+                    #name::#id { .. } => ::thisdiagnostic::Severity::#severity,
+                },
+                (None, None, None) => quote! {
+                    #name::#id { .. } => ::thisdiagnostic::Severity::default(),
+                },
+            }
+        })
+        .collect::<Vec<_>>();
+    let severity = has_severity.then(|| {
+        quote! {
+            fn severity(&self) -> ::thisdiagnostic::Severity {
+                match self {
+                    #(#severity_arms)*
+                }
+            }
+        }
+    });
+
+    let mut has_url = input.attrs.url.is_some();
+    let url_arms = input
+        .variants
+        .iter()
+        .map(|variant| {
+            let id = &variant.ident;
+            let url = variant.attrs.url.as_ref();
+            Ok(match (url, variant.ask_field(), &input.attrs.url) {
+                (Some(url), _, _) | (None, None, Some(url)) => {
+                    has_url = true;
+                    let url = Format::parse_url(url, &variant.fields)?;
+                    let bindings = url.bindings();
+                    let url = url.expand();
+                    quote! {
+                        #name::#id { #bindings .. } => Some(#url),
+                    }
+                }
+                (None, Some(ask), _) => {
+                    has_url = true;
+                    let member = &ask.member;
+                    quote! {
+                        #name::#id { #member: err, .. } => err.url(),
+                    }
+                }
+                (None, None, None) => quote! {
+                    #name::#id { .. } => None,
+                },
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let url = has_url.then(|| {
+        quote! {
+            fn url(&self) -> Option<String> {
+                match self {
+                    #(#url_arms)*
+                }
+            }
+        }
+    });
+
+    let labels = (!asks.is_empty()).then(|| {
+        let arms = input.variants.iter().map(|variant| {
+            let id = &variant.ident;
+            match variant.ask_field() {
+                Some(ask) => {
+                    let member = &ask.member;
+                    quote! {
+                        #name::#id { #member: err, .. } => err.labels(),
+                    }
+                }
+                None => quote! {
+                    #name::#id { .. } => Vec::new(),
+                },
+            }
+        });
+        quote! {
+            fn labels(&self) -> Vec<::thisdiagnostic::LabeledSpan> {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    });
+
+    let has_source = input
+        .variants
+        .iter()
+        .any(|variant| variant.source_field().is_some());
+    let diagnostic_source = has_source.then(|| {
+        let arms = input.variants.iter().map(|variant| {
+            let id = &variant.ident;
+            match variant.source_field() {
+                Some(source) => {
+                    let member = &source.member;
+                    let source = as_diagnostic_source(quote! { source });
+                    quote! {
+                        #name::#id { #member: source, .. } => #source,
+                    }
+                }
+                None => quote! {
+                    #name::#id { .. } => None,
+                },
+            }
+        });
+        quote! {
+            fn diagnostic_source(&self) -> Option<&dyn ::thisdiagnostic::Diagnostic> {
+                match self {
+                    #(#arms)*
+                }
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics Diagnostic for #name #ty_generics #where_clause {
+            fn label(&self) -> String {
+                match self {
+                    #(#label_arms)*
+                }
+            }
+
+            fn help(&self) -> Option<String> {
+                match self {
+                    #(#help_arms)*
+                }
+            }
+
+            #meta
+            #severity
+            #url
+            #labels
+            #diagnostic_source
+        }
+    })
+}
+
+/**
+`field` (a reference) as a `&dyn Diagnostic` if its type implements
+`Diagnostic`, or `None` if it doesn't. Plain errors are allowed as sources,
+so this can't just require the trait.
+*/
+fn as_diagnostic_source(field: TokenStream) -> TokenStream {
+    quote! {
+        {
+            #[allow(unused_imports)]
+            use ::thisdiagnostic::__private::{AsDiagnosticSource as _, NotDiagnosticSource as _};
+            (&::thisdiagnostic::__private::Wrap(#field)).as_diagnostic_source()
+        }
+    }
+}
+
+/**
+Prepends the container's `#[label(prefix = "...")]`, if any, to a label.
+*/
+fn prefixed(label: &LitStr, prefix: Option<&LitStr>) -> LitStr {
+    match prefix {
+        Some(prefix) => {
+            let prefix = prefix.value().replace('{', "{{").replace('}', "}}");
+            LitStr::new(&format!("{}::{}", prefix, label.value()), label.span())
+        }
+        None => label.clone(),
+    }
+}
+
+/**
+The label used when none is given: the type (and variant) name, under the
+container's label prefix or, failing that, the module it was defined in.
+*/
+fn default_label(prefix: Option<&LitStr>, path: &str) -> TokenStream {
+    match prefix {
+        Some(prefix) => {
+            let label = format!("{}::{}", prefix.value(), path);
+            quote! { ::std::string::String::from(#label) }
+        }
+        None => {
+            let path = format!("::{}", path);
+            quote! {
+                ::std::string::String::from(::std::concat!(::std::module_path!(), #path))
+            }
+        }
+    }
+}
+
+/**
+The help used when none is given: the doc comment, if the container opted in
+with `#[diagnostic(help_from_docs)]`.
+*/
+fn docs_help(container: &Attrs, attrs: &Attrs) -> TokenStream {
+    match (&container.help_from_docs, &attrs.docs) {
+        (Some(_), Some(docs)) => quote! { Some(::std::string::String::from(#docs)) },
+        _ => quote! { None },
+    }
+}
+
+/**
+Adds the bounds `Diagnostic` needs to the type's own generics: every type
+parameter must be `Send + Sync + 'static`, every lifetime must be `'static`,
+and any `#[ask]` field whose type mentions a type parameter must itself be a
+`Diagnostic`.
+*/
+fn with_bounds<'a>(generics: &Generics, asks: impl IntoIterator<Item = &'a Type>) -> Generics {
+    let mut generics = generics.clone();
+    let params = generics
+        .params
+        .iter()
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => Some(param.ident.clone()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut predicates: Vec<WherePredicate> = Vec::new();
+    for param in &generics.params {
+        match param {
+            GenericParam::Type(param) => {
+                let ident = &param.ident;
+                predicates.push(parse_quote! { #ident: Send + Sync + 'static });
+            }
+            GenericParam::Lifetime(param) => {
+                let lifetime = &param.lifetime;
+                predicates.push(parse_quote! { #lifetime: 'static });
+            }
+            GenericParam::Const(_) => {}
+        }
+    }
+    for ty in asks {
+        if mentions_any(quote!(#ty), &params) {
+            predicates.push(parse_quote! { #ty: Diagnostic });
+        }
+    }
+
+    generics.make_where_clause().predicates.extend(predicates);
+    generics
+}
+
+fn mentions_any(tokens: TokenStream, idents: &[syn::Ident]) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => idents.contains(&ident),
+        TokenTree::Group(group) => mentions_any(group.stream(), idents),
+        _ => false,
+    })
+}
@@ -0,0 +1,160 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Error, Ident, Index, LitStr, Member, Result};
+
+use crate::ast::Field;
+
+/**
+A `label`, `help` or `url` string with its `{field}` and `{0}` references
+resolved against the fields of the struct or variant it is attached to.
+*/
+pub struct Format {
+    fmt: LitStr,
+    args: Vec<(Member, Ident)>,
+    uses_label: bool,
+}
+
+impl Format {
+    pub fn parse(lit: &LitStr, fields: &[Field]) -> Result<Self> {
+        Format::parse_inner(lit, fields, false)
+    }
+
+    /**
+    Like [`Format::parse`], but `{label}` refers to the diagnostic's label
+    rather than to a field.
+    */
+    pub fn parse_url(lit: &LitStr, fields: &[Field]) -> Result<Self> {
+        Format::parse_inner(lit, fields, true)
+    }
+
+    fn parse_inner(lit: &LitStr, fields: &[Field], allow_label: bool) -> Result<Self> {
+        let value = lit.value();
+        let mut fmt = String::with_capacity(value.len());
+        let mut args: Vec<(Member, Ident)> = Vec::new();
+        let mut uses_label = false;
+        let mut chars = value.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            fmt.push(c);
+            if c == '}' {
+                if chars.peek() == Some(&'}') {
+                    fmt.push(chars.next().unwrap());
+                }
+                continue;
+            }
+            if c != '{' {
+                continue;
+            }
+            if chars.peek() == Some(&'{') {
+                fmt.push(chars.next().unwrap());
+                continue;
+            }
+
+            let mut name = String::new();
+            while let Some(&c) = chars.peek() {
+                if c == '}' || c == ':' {
+                    break;
+                }
+                name.push(c);
+                chars.next();
+            }
+
+            let name = name.trim();
+            if allow_label && name == "label" {
+                fmt.push_str("label");
+                uses_label = true;
+                continue;
+            }
+            let member = if name.is_empty() {
+                return Err(Error::new_spanned(
+                    lit,
+                    "positional `{}` is not supported, refer to a field by name or index instead",
+                ));
+            } else if let Ok(index) = name.parse::<u32>() {
+                Member::Unnamed(Index {
+                    index,
+                    span: lit.span(),
+                })
+            } else {
+                match syn::parse_str::<Ident>(name) {
+                    Ok(mut ident) => {
+                        ident.set_span(lit.span());
+                        Member::Named(ident)
+                    }
+                    Err(_) => {
+                        return Err(Error::new_spanned(
+                            lit,
+                            format!("invalid field reference `{{{}}}`", name),
+                        ))
+                    }
+                }
+            };
+
+            if !fields.iter().any(|field| field.member == member) {
+                let message = match &member {
+                    Member::Named(ident) => format!("no field named `{}`", ident),
+                    Member::Unnamed(index) => format!("no field at index {}", index.index),
+                };
+                return Err(Error::new_spanned(lit, message));
+            }
+
+            let binding = match &member {
+                Member::Named(ident) => {
+                    let mut binding = ident.clone();
+                    binding.set_span(Span::call_site());
+                    binding
+                }
+                Member::Unnamed(index) => format_ident!("_{}", index.index),
+            };
+            fmt.push_str(&format_var(&binding).to_string());
+            if !args.iter().any(|(existing, _)| *existing == member) {
+                args.push((member, binding));
+            }
+        }
+
+        Ok(Format {
+            fmt: LitStr::new(&fmt, lit.span()),
+            args,
+            uses_label,
+        })
+    }
+
+    /**
+    Field patterns binding every referenced field, e.g. `name: name, 0: _0`.
+    */
+    pub fn bindings(&self) -> TokenStream {
+        let bindings = self
+            .args
+            .iter()
+            .map(|(member, binding)| quote! { #member: #binding });
+        quote! { #(#bindings,)* }
+    }
+
+    /**
+    A `String` expression formatting this string with the bound fields.
+    */
+    pub fn expand(&self) -> TokenStream {
+        let fmt = &self.fmt;
+        let args = self.args.iter().map(|(_, binding)| {
+            let var = format_var(binding);
+            quote! { #var = #binding }
+        });
+        let label = self.uses_label.then(|| {
+            quote! { , label = Diagnostic::label(self) }
+        });
+        quote! {
+            ::std::format!(#fmt #(, #args)* #label)
+        }
+    }
+}
+
+/**
+The name a bound field goes by in the format string. Format strings don't
+take raw identifiers, so `r#type` becomes `r_type`, like thiserror does.
+*/
+fn format_var(binding: &Ident) -> Ident {
+    match binding.to_string().strip_prefix("r#") {
+        Some(name) => format_ident!("r_{}", name),
+        None => binding.clone(),
+    }
+}
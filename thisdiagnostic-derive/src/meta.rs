@@ -0,0 +1,180 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Error, Ident, Member, Result};
+
+use crate::ast::Field;
+use crate::attr::MetaKind;
+
+/**
+The `DiagnosticMetadata` described by the `#[meta(...)]` fields of a struct
+or variant.
+*/
+pub struct FieldMeta {
+    path: Option<Member>,
+    url: Option<Member>,
+    input: Option<Member>,
+    row: Option<Member>,
+    col: Option<Member>,
+    span: Option<Member>,
+}
+
+impl FieldMeta {
+    pub fn from_fields(fields: &[Field]) -> Result<Option<Self>> {
+        let mut meta = FieldMeta {
+            path: None,
+            url: None,
+            input: None,
+            row: None,
+            col: None,
+            span: None,
+        };
+        let mut first = None;
+
+        for field in fields {
+            let attr = match &field.attrs.meta {
+                Some(attr) => attr,
+                None => continue,
+            };
+            let (slot, name) = match attr.kind {
+                MetaKind::Path => (&mut meta.path, "path"),
+                MetaKind::Url => (&mut meta.url, "url"),
+                MetaKind::Input => (&mut meta.input, "input"),
+                MetaKind::Row => (&mut meta.row, "row"),
+                MetaKind::Col => (&mut meta.col, "col"),
+                MetaKind::Span => (&mut meta.span, "span"),
+            };
+            if slot.is_some() {
+                return Err(Error::new_spanned(
+                    &attr.original,
+                    format!("only one field may be marked `meta({})`", name),
+                ));
+            }
+            *slot = Some(field.member.clone());
+            first.get_or_insert(&attr.original);
+        }
+
+        let first = match first {
+            Some(first) => first,
+            None => return Ok(None),
+        };
+        if let Some(ask) = fields.iter().find_map(|field| field.attrs.ask.as_ref()) {
+            return Err(Error::new_spanned(
+                ask,
+                "`ask` already provides metadata, remove the `meta` fields",
+            ));
+        }
+        if meta.url.is_some()
+            && (meta.path.is_some()
+                || meta.input.is_some()
+                || meta.row.is_some()
+                || meta.col.is_some()
+                || meta.span.is_some())
+        {
+            return Err(Error::new_spanned(
+                first,
+                "`meta(url)` cannot be combined with other `meta` fields",
+            ));
+        }
+        if meta.span.is_some() {
+            if meta.row.is_some() || meta.col.is_some() {
+                return Err(Error::new_spanned(
+                    first,
+                    "`meta(span)` cannot be combined with `meta(row)` or `meta(col)`",
+                ));
+            }
+            if meta.input.is_none() {
+                return Err(Error::new_spanned(
+                    first,
+                    "span metadata needs both `meta(input)` and `meta(span)`",
+                ));
+            }
+            return Ok(Some(meta));
+        }
+        let parse = [&meta.input, &meta.row, &meta.col];
+        if parse.iter().any(|member| member.is_some())
+            && !parse.iter().all(|member| member.is_some())
+        {
+            return Err(Error::new_spanned(
+                first,
+                "parse metadata needs all of `meta(input)`, `meta(row)` and `meta(col)`",
+            ));
+        }
+
+        Ok(Some(meta))
+    }
+
+    /**
+    Field patterns binding every field this metadata is built from.
+    */
+    pub fn bindings(&self) -> TokenStream {
+        let bindings = self.members().map(|(member, binding)| {
+            quote! { #member: #binding }
+        });
+        quote! { #(#bindings,)* }
+    }
+
+    /**
+    A `DiagnosticMetadata` expression built from the bound fields.
+    */
+    pub fn expand(&self) -> TokenStream {
+        let path = self.path.as_ref().map(|_| {
+            let binding = binding("path");
+            quote! { ::std::path::Path::new(#binding).to_path_buf() }
+        });
+        let optional_path = match &path {
+            Some(path) => quote! { Some(#path) },
+            None => quote! { None },
+        };
+
+        if self.url.is_some() {
+            let url = binding("url");
+            quote! {
+                ::thisdiagnostic::DiagnosticMetadata::Net {
+                    url: ::std::string::ToString::to_string(#url),
+                }
+            }
+        } else if self.span.is_some() {
+            let (input, span) = (binding("input"), binding("span"));
+            quote! {
+                ::thisdiagnostic::DiagnosticMetadata::ParseSpan {
+                    input: ::std::string::ToString::to_string(#input),
+                    span: ::thisdiagnostic::SourceSpan::from(::std::clone::Clone::clone(#span)),
+                    path: #optional_path,
+                }
+            }
+        } else if self.input.is_some() {
+            let (input, row, col) = (binding("input"), binding("row"), binding("col"));
+            quote! {
+                ::thisdiagnostic::DiagnosticMetadata::Parse {
+                    input: ::std::string::ToString::to_string(#input),
+                    row: *#row as usize,
+                    col: *#col as usize,
+                    path: #optional_path,
+                }
+            }
+        } else {
+            quote! {
+                ::thisdiagnostic::DiagnosticMetadata::Fs {
+                    path: #path,
+                }
+            }
+        }
+    }
+
+    fn members(&self) -> impl Iterator<Item = (&Member, Ident)> {
+        vec![
+            (&self.path, "path"),
+            (&self.url, "url"),
+            (&self.input, "input"),
+            (&self.row, "row"),
+            (&self.col, "col"),
+            (&self.span, "span"),
+        ]
+        .into_iter()
+        .filter_map(|(member, name)| member.as_ref().map(|member| (member, binding(name))))
+    }
+}
+
+fn binding(name: &str) -> Ident {
+    format_ident!("__meta_{}", name)
+}